@@ -4,7 +4,7 @@ use std::{sync::Arc, time::Instant};
 
 use egui_glow::glow::HasContext;
 use egui_sdl2_platform::sdl2;
-use sdl2::event::{Event, WindowEvent};
+use sdl2::event::Event;
 use timestep::TimeStep;
 
 const SCREEN_WIDTH: u32 = 800;
@@ -19,10 +19,11 @@ async fn run() -> anyhow::Result<()> {
         .video()
         .map_err(|e| anyhow::anyhow!("Failed to initialize sdl video subsystem: {}", e))?;
     // Create the sdl window
-    let window = video
+    let mut window = video
         .window("Window", SCREEN_WIDTH, SCREEN_HEIGHT)
         .opengl()
         .position_centered()
+        .allow_highdpi()
         .build()?;
     // Get the sdl event pump
     let mut event_pump = sdl
@@ -56,6 +57,8 @@ async fn run() -> anyhow::Result<()> {
         // Update the time
         platform.update_time(start_time.elapsed().as_secs_f64());
 
+        // Follow the window size and display scale
+        platform.update_window(&window);
         // Get the egui context and begin drawing the frame
         let ctx = platform.context();
         // Draw an egui window
@@ -73,6 +76,12 @@ async fn run() -> anyhow::Result<()> {
 
         // Stop drawing the egui frame and get the full output
         let full_output = platform.end_frame(&mut video)?;
+        // Apply the window changes egui asked for
+        platform.apply_viewport_commands(&mut window, &full_output);
+        // Stop once closing was requested and egui did not cancel it
+        if platform.should_close() {
+            break 'main;
+        }
         // Get the paint jobs
         let paint_jobs = platform.tessellate(&full_output);
         let pj = paint_jobs.as_slice();
@@ -82,31 +91,25 @@ async fn run() -> anyhow::Result<()> {
             painter.gl().clear(gl::COLOR_BUFFER_BIT);
         }
 
-        let size = window.size();
-        painter.paint_and_update_textures([size.0, size.1], 1.0, pj, &full_output.textures_delta);
+        let size = window.drawable_size();
+        painter.paint_and_update_textures(
+            [size.0, size.1],
+            full_output.pixels_per_point,
+            pj,
+            &full_output.textures_delta,
+        );
         window.gl_swap_window();
         timestep.run_this(|_| {});
 
         // Handle sdl events
         for event in event_pump.poll_iter() {
             // Handle sdl events
-            match event {
-                Event::Window {
-                    window_id,
-                    win_event,
-                    ..
-                } => {
-                    if window_id == window.id() {
-                        if let WindowEvent::Close = win_event {
-                            break 'main;
-                        }
-                    }
-                }
-                Event::KeyDown {
-                    keycode: Some(sdl2::keyboard::Keycode::Escape),
-                    ..
-                } => break 'main,
-                _ => {}
+            if let Event::KeyDown {
+                keycode: Some(sdl2::keyboard::Keycode::Escape),
+                ..
+            } = event
+            {
+                break 'main;
             }
             // Let the egui platform handle the event
             platform.handle_event(&event, &sdl, &video);
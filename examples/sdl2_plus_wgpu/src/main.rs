@@ -13,9 +13,10 @@ async fn run() -> anyhow::Result<()> {
         .video()
         .map_err(|e| anyhow::anyhow!("Failed to initialize sdl video subsystem: {}", e))?;
     // Create the sdl window
-    let window = video
+    let mut window = video
         .window("With love <3 - GetAGripGal", 1280, 720)
         .position_centered()
+        .allow_highdpi()
         .resizable()
         .build()?;
     // Get the sdl event pump
@@ -52,8 +53,8 @@ async fn run() -> anyhow::Result<()> {
         format: surface_format,
         view_formats: vec![wgpu::TextureFormat::Bgra8UnormSrgb],
         alpha_mode: wgpu::CompositeAlphaMode::Opaque,
-        width: window.size().0,
-        height: window.size().1,
+        width: window.drawable_size().0,
+        height: window.drawable_size().1,
         present_mode: wgpu::PresentMode::AutoVsync,
         desired_maximum_frame_latency: 1,
     };
@@ -77,6 +78,8 @@ async fn run() -> anyhow::Result<()> {
         // Update the time
         platform.update_time(start_time.elapsed().as_secs_f64());
 
+        // Follow the window size and display scale
+        platform.update_window(&window);
         // Get the egui context and begin drawing the frame
         let ctx = platform.context();
         // Draw an egui window
@@ -99,6 +102,12 @@ async fn run() -> anyhow::Result<()> {
 
         // Stop drawing the egui frame and get the full output
         let full_output = platform.end_frame(&mut video)?;
+        // Apply the window changes egui asked for
+        platform.apply_viewport_commands(&mut window, &full_output);
+        // Stop once closing was requested and egui did not cancel it
+        if platform.should_close() {
+            break 'main;
+        }
         // Get the paint jobs
         let paint_jobs = platform.tessellate(&full_output);
 
@@ -134,8 +143,7 @@ async fn run() -> anyhow::Result<()> {
         let screen_descriptor = egui_wgpu_backend::ScreenDescriptor {
             physical_width: surface_config.width,
             physical_height: surface_config.height,
-            // The sdl scale factor remains constant, so we shall set it to 1
-            scale_factor: 1.0,
+            scale_factor: full_output.pixels_per_point,
         };
         // Add the textures to the egui render pass
         let tdelta = full_output.textures_delta;
@@ -156,23 +164,19 @@ async fn run() -> anyhow::Result<()> {
         // Handle sdl events
         for event in event_pump.poll_iter() {
             // Handle sdl events
-            match event {
-                Event::Window {
-                    window_id,
-                    win_event,
-                    ..
-                } if window_id == window.id() => match win_event {
-                    WindowEvent::Close => break 'main,
-                    WindowEvent::SizeChanged(w, h) => {
-                        if w > 0 && h > 0 {
-                            surface_config.width = w as u32;
-                            surface_config.height = h as u32;
-                            surface.configure(&device, &surface_config);
-                        }
-                    }
-                    _ => {}
-                },
-                _ => {}
+            if let Event::Window {
+                window_id,
+                win_event: WindowEvent::SizeChanged(w, h),
+                ..
+            } = event
+            {
+                if window_id == window.id() && w > 0 && h > 0 {
+                    // The surface is sized in pixels, which differ from window units on high dpi
+                    let (width, height) = window.drawable_size();
+                    surface_config.width = width;
+                    surface_config.height = height;
+                    surface.configure(&device, &surface_config);
+                }
             }
             // Let the egui platform handle the event
             platform.handle_event(&event, &sdl, &video);
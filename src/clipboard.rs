@@ -0,0 +1,322 @@
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+/// A clipboard action that can be bound to a keyboard shortcut
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardAction {
+    /// Copy the selection
+    Copy,
+    /// Cut the selection
+    Cut,
+    /// Paste the clipboard contents
+    Paste,
+}
+
+/// The keyboard shortcuts that trigger clipboard actions
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardShortcuts {
+    /// The shortcuts and the actions they trigger
+    pub bindings: Vec<(KeyboardShortcut, ClipboardAction)>,
+}
+
+impl ClipboardShortcuts {
+    /// Construct shortcuts without any bindings
+    pub fn empty() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Bind a shortcut to an action
+    pub fn bind(mut self, shortcut: KeyboardShortcut, action: ClipboardAction) -> Self {
+        self.bindings.push((shortcut, action));
+        self
+    }
+
+    /// Find the action bound to a key pressed with the given modifiers
+    pub fn action(&self, modifiers: Modifiers, key: Key) -> Option<ClipboardAction> {
+        self.bindings
+            .iter()
+            .find(|(shortcut, _)| {
+                shortcut.logical_key == key && modifiers.matches_exact(shortcut.modifiers)
+            })
+            .map(|(_, action)| *action)
+    }
+}
+
+impl Default for ClipboardShortcuts {
+    fn default() -> Self {
+        let shortcuts = Self::empty()
+            .bind(
+                KeyboardShortcut::new(Modifiers::COMMAND, Key::C),
+                ClipboardAction::Copy,
+            )
+            .bind(
+                KeyboardShortcut::new(Modifiers::COMMAND, Key::X),
+                ClipboardAction::Cut,
+            )
+            .bind(
+                KeyboardShortcut::new(Modifiers::COMMAND, Key::V),
+                ClipboardAction::Paste,
+            )
+            .bind(
+                KeyboardShortcut::new(Modifiers::COMMAND, Key::Insert),
+                ClipboardAction::Copy,
+            )
+            .bind(
+                KeyboardShortcut::new(Modifiers::SHIFT, Key::Insert),
+                ClipboardAction::Paste,
+            )
+            .bind(
+                KeyboardShortcut::new(Modifiers::NONE, Key::Copy),
+                ClipboardAction::Copy,
+            )
+            .bind(
+                KeyboardShortcut::new(Modifiers::NONE, Key::Cut),
+                ClipboardAction::Cut,
+            )
+            .bind(
+                KeyboardShortcut::new(Modifiers::NONE, Key::Paste),
+                ClipboardAction::Paste,
+            );
+        // Egui deletes the next character with shift+delete elsewhere, only windows cuts with it
+        if cfg!(windows) {
+            shortcuts.bind(
+                KeyboardShortcut::new(Modifiers::SHIFT, Key::Delete),
+                ClipboardAction::Cut,
+            )
+        } else {
+            shortcuts
+        }
+    }
+}
+
+/// A clipboard the platform reads and writes text through
+pub trait ClipboardProvider {
+    /// Get the clipboard text, `None` when the clipboard holds no text
+    fn text(&mut self) -> anyhow::Result<Option<String>>;
+
+    /// Replace the clipboard text
+    fn set_text(&mut self, text: &str) -> anyhow::Result<()>;
+
+    /// Get the primary selection text, `None` when there is no primary selection
+    fn primary_selection(&mut self) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Replace the primary selection text, ignored where there is no primary selection
+    fn set_primary_selection(&mut self, _text: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Replace the clipboard contents with an image
+    fn set_image(&mut self, _image: &egui::ColorImage) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("The clipboard does not support images"))
+    }
+}
+
+/// Called with an image the clipboard could not take, e.g. to save it to a file instead
+pub type CopyImageFallback = Box<dyn FnMut(&egui::ColorImage) -> anyhow::Result<()>>;
+
+/// The system clipboard provided by sdl2.
+///
+/// Sdl2 only handles text, images are copied through arboard with the `clipboard_images` feature.
+pub struct SdlClipboard {
+    clipboard: sdl2::clipboard::ClipboardUtil,
+    // The clipboard images go through, kept alive so it keeps serving them on X11 and Wayland
+    #[cfg(feature = "clipboard_images")]
+    image_clipboard: Option<arboard::Clipboard>,
+}
+
+impl SdlClipboard {
+    /// Whether the windowing system is X11 or Wayland, the only ones with a primary selection
+    const IS_X11_OR_WAYLAND: bool = cfg!(all(
+        unix,
+        not(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "android"
+        ))
+    ));
+
+    /// Construct a new [`SdlClipboard`]
+    pub fn new(video: &sdl2::VideoSubsystem) -> Self {
+        Self {
+            clipboard: video.clipboard(),
+            #[cfg(feature = "clipboard_images")]
+            image_clipboard: None,
+        }
+    }
+}
+
+impl ClipboardProvider for SdlClipboard {
+    fn text(&mut self) -> anyhow::Result<Option<String>> {
+        if !self.clipboard.has_clipboard_text() {
+            return Ok(None);
+        }
+        self.clipboard
+            .clipboard_text()
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("Failed to get clipboard text: {}", e))
+    }
+
+    fn set_text(&mut self, text: &str) -> anyhow::Result<()> {
+        self.clipboard
+            .set_clipboard_text(text)
+            .map_err(|e| anyhow::anyhow!("Failed to assign text to clipboard: {}", e))
+    }
+
+    fn primary_selection(&mut self) -> anyhow::Result<Option<String>> {
+        if !Self::IS_X11_OR_WAYLAND || !self.clipboard.has_primary_selection_text() {
+            return Ok(None);
+        }
+        self.clipboard
+            .primary_selection_text()
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("Failed to get primary selection text: {}", e))
+    }
+
+    #[cfg(feature = "clipboard_images")]
+    fn set_image(&mut self, image: &egui::ColorImage) -> anyhow::Result<()> {
+        let clipboard = match &mut self.image_clipboard {
+            Some(clipboard) => clipboard,
+            None => self.image_clipboard.insert(
+                arboard::Clipboard::new()
+                    .map_err(|e| anyhow::anyhow!("Failed to open the clipboard: {}", e))?,
+            ),
+        };
+        let bytes = image
+            .pixels
+            .iter()
+            .flat_map(|pixel| pixel.to_srgba_unmultiplied())
+            .collect::<Vec<u8>>();
+        clipboard
+            .set_image(arboard::ImageData {
+                width: image.width(),
+                height: image.height(),
+                bytes: bytes.into(),
+            })
+            .map_err(|e| anyhow::anyhow!("Failed to assign image to clipboard: {}", e))
+    }
+
+    fn set_primary_selection(&mut self, text: &str) -> anyhow::Result<()> {
+        if !Self::IS_X11_OR_WAYLAND {
+            return Ok(());
+        }
+        self.clipboard
+            .set_primary_selection_text(text)
+            .map_err(|e| anyhow::anyhow!("Failed to assign text to primary selection: {}", e))
+    }
+}
+
+/// A clipboard that lives in memory, useful for tests and headless setups
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryClipboard {
+    /// The clipboard text
+    pub text: Option<String>,
+    /// The clipboard image
+    pub image: Option<egui::ColorImage>,
+    /// The primary selection text
+    pub primary_selection: Option<String>,
+}
+
+impl ClipboardProvider for MemoryClipboard {
+    fn text(&mut self) -> anyhow::Result<Option<String>> {
+        Ok(self.text.clone())
+    }
+
+    fn set_text(&mut self, text: &str) -> anyhow::Result<()> {
+        self.text = Some(text.to_owned());
+        Ok(())
+    }
+
+    fn primary_selection(&mut self) -> anyhow::Result<Option<String>> {
+        Ok(self.primary_selection.clone())
+    }
+
+    fn set_primary_selection(&mut self, text: &str) -> anyhow::Result<()> {
+        self.primary_selection = Some(text.to_owned());
+        Ok(())
+    }
+
+    fn set_image(&mut self, image: &egui::ColorImage) -> anyhow::Result<()> {
+        self.image = Some(image.clone());
+        Ok(())
+    }
+}
+
+/// Get the text selected in a text edit from a selection changed event
+pub(crate) fn selected_text(info: &egui::WidgetInfo) -> Option<String> {
+    let text = info.current_text_value.as_ref()?;
+    // Password fields report their text masked, which must not replace the primary selection
+    if text.chars().all(|c| c == '•') {
+        return None;
+    }
+    let range = info.text_selection.as_ref()?;
+    // The range goes from the primary to the secondary cursor, in either order
+    let (start, end) = if range.start() <= range.end() {
+        (*range.start(), *range.end())
+    } else {
+        (*range.end(), *range.start())
+    };
+    if start == end {
+        return None;
+    }
+    Some(text.chars().skip(start).take(end - start).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::RangeInclusive;
+
+    #[test]
+    fn plain_letters_do_not_trigger_actions() {
+        let shortcuts = ClipboardShortcuts::default();
+        assert_eq!(shortcuts.action(Modifiers::NONE, Key::C), None);
+        assert_eq!(shortcuts.action(Modifiers::SHIFT, Key::V), None);
+    }
+
+    #[test]
+    fn shift_delete_only_cuts_on_windows() {
+        let shortcuts = ClipboardShortcuts::default();
+        assert_eq!(
+            shortcuts.action(Modifiers::SHIFT, Key::Delete),
+            cfg!(windows).then_some(ClipboardAction::Cut)
+        );
+    }
+
+    #[test]
+    fn selected_text_handles_backwards_selections() {
+        let info = egui::WidgetInfo::text_selection_changed(
+            true,
+            RangeInclusive::new(9, 4),
+            "grüß dich welt",
+        );
+        assert_eq!(selected_text(&info).as_deref(), Some(" dich"));
+
+        let info = egui::WidgetInfo::text_selection_changed(true, 3..=3, "grüß");
+        assert_eq!(selected_text(&info), None);
+    }
+
+    #[test]
+    fn selected_text_skips_password_fields() {
+        let info = egui::WidgetInfo::text_selection_changed(true, 0..=4, "••••••");
+        assert_eq!(selected_text(&info), None);
+    }
+
+    #[test]
+    fn default_chords_trigger_actions() {
+        let shortcuts = ClipboardShortcuts::default();
+        let ctrl = Modifiers::CTRL | Modifiers::COMMAND;
+        assert_eq!(shortcuts.action(ctrl, Key::C), Some(ClipboardAction::Copy));
+        assert_eq!(shortcuts.action(ctrl, Key::X), Some(ClipboardAction::Cut));
+        assert_eq!(
+            shortcuts.action(Modifiers::SHIFT, Key::Insert),
+            Some(ClipboardAction::Paste)
+        );
+        assert_eq!(
+            shortcuts.action(Modifiers::NONE, Key::Paste),
+            Some(ClipboardAction::Paste)
+        );
+    }
+}
@@ -1,5 +1,8 @@
-use egui::Key;
-use sdl2::keyboard::Keycode;
+use egui::{CursorIcon, Key, PointerButton};
+use sdl2::{
+    keyboard::{Keycode, Mod, Scancode},
+    mouse::{MouseButton, SystemCursor},
+};
 
 /// A trait that adds a method to convert to an egui key
 pub trait ToEguiKey {
@@ -7,6 +10,7 @@ pub trait ToEguiKey {
     fn to_egui_key(&self) -> Option<egui::Key>;
 }
 
+/// Every egui key is covered except `F25` to `F35`, which have no sdl2 keycode.
 impl ToEguiKey for sdl2::keyboard::Keycode {
     fn to_egui_key(&self) -> Option<egui::Key> {
         Some(match *self {
@@ -15,16 +19,37 @@ impl ToEguiKey for sdl2::keyboard::Keycode {
             Keycode::Right => Key::ArrowRight,
             Keycode::Down => Key::ArrowDown,
             Keycode::Escape => Key::Escape,
-            Keycode::Tab => Key::Tab,
-            Keycode::Backspace => Key::Backspace,
-            Keycode::Space => Key::Space,
-            Keycode::Return => Key::Enter,
+            Keycode::Tab | Keycode::KpTab => Key::Tab,
+            Keycode::Backspace | Keycode::KpBackspace => Key::Backspace,
+            Keycode::Space | Keycode::KpSpace => Key::Space,
+            Keycode::Return | Keycode::Return2 | Keycode::KpEnter => Key::Enter,
             Keycode::Insert => Key::Insert,
             Keycode::Home => Key::Home,
             Keycode::Delete => Key::Delete,
             Keycode::End => Key::End,
             Keycode::PageDown => Key::PageDown,
             Keycode::PageUp => Key::PageUp,
+            Keycode::Copy => Key::Copy,
+            Keycode::Cut => Key::Cut,
+            Keycode::Paste => Key::Paste,
+            Keycode::Colon | Keycode::KpColon => Key::Colon,
+            Keycode::Comma | Keycode::KpComma => Key::Comma,
+            Keycode::Backslash => Key::Backslash,
+            Keycode::Slash | Keycode::KpDivide => Key::Slash,
+            Keycode::KpVerticalBar => Key::Pipe,
+            Keycode::Question => Key::Questionmark,
+            Keycode::Exclaim | Keycode::KpExclam => Key::Exclamationmark,
+            Keycode::LeftBracket => Key::OpenBracket,
+            Keycode::RightBracket => Key::CloseBracket,
+            Keycode::KpLeftBrace => Key::OpenCurlyBracket,
+            Keycode::KpRightBrace => Key::CloseCurlyBracket,
+            Keycode::Backquote => Key::Backtick,
+            Keycode::Minus | Keycode::KpMinus => Key::Minus,
+            Keycode::Period | Keycode::KpPeriod => Key::Period,
+            Keycode::Plus | Keycode::KpPlus => Key::Plus,
+            Keycode::Equals | Keycode::KpEquals => Key::Equals,
+            Keycode::Semicolon => Key::Semicolon,
+            Keycode::Quote | Keycode::Quotedbl => Key::Quote,
             Keycode::Kp0 | Keycode::Num0 => Key::Num0,
             Keycode::Kp1 | Keycode::Num1 => Key::Num1,
             Keycode::Kp2 | Keycode::Num2 => Key::Num2,
@@ -61,9 +86,422 @@ impl ToEguiKey for sdl2::keyboard::Keycode {
             Keycode::X => Key::X,
             Keycode::Y => Key::Y,
             Keycode::Z => Key::Z,
+            Keycode::F1 => Key::F1,
+            Keycode::F2 => Key::F2,
+            Keycode::F3 => Key::F3,
+            Keycode::F4 => Key::F4,
+            Keycode::F5 => Key::F5,
+            Keycode::F6 => Key::F6,
+            Keycode::F7 => Key::F7,
+            Keycode::F8 => Key::F8,
+            Keycode::F9 => Key::F9,
+            Keycode::F10 => Key::F10,
+            Keycode::F11 => Key::F11,
+            Keycode::F12 => Key::F12,
+            Keycode::F13 => Key::F13,
+            Keycode::F14 => Key::F14,
+            Keycode::F15 => Key::F15,
+            Keycode::F16 => Key::F16,
+            Keycode::F17 => Key::F17,
+            Keycode::F18 => Key::F18,
+            Keycode::F19 => Key::F19,
+            Keycode::F20 => Key::F20,
+            Keycode::F21 => Key::F21,
+            Keycode::F22 => Key::F22,
+            Keycode::F23 => Key::F23,
+            Keycode::F24 => Key::F24,
+            Keycode::AcBack => Key::BrowserBack,
             _ => {
                 return None;
             }
         })
     }
 }
+
+/// A trait that adds a method to convert to a physical egui key
+pub trait ToEguiPhysicalKey {
+    /// Convert the struct to the egui key at the same position on a US keyboard layout
+    fn to_egui_physical_key(&self) -> Option<egui::Key>;
+}
+
+impl ToEguiPhysicalKey for sdl2::keyboard::Scancode {
+    fn to_egui_physical_key(&self) -> Option<egui::Key> {
+        Some(match *self {
+            Scancode::Left => Key::ArrowLeft,
+            Scancode::Up => Key::ArrowUp,
+            Scancode::Right => Key::ArrowRight,
+            Scancode::Down => Key::ArrowDown,
+            Scancode::Escape => Key::Escape,
+            Scancode::Tab | Scancode::KpTab => Key::Tab,
+            Scancode::Backspace | Scancode::KpBackspace => Key::Backspace,
+            Scancode::Space | Scancode::KpSpace => Key::Space,
+            Scancode::Return | Scancode::Return2 | Scancode::KpEnter => Key::Enter,
+            Scancode::Insert => Key::Insert,
+            Scancode::Home => Key::Home,
+            Scancode::Delete => Key::Delete,
+            Scancode::End => Key::End,
+            Scancode::PageDown => Key::PageDown,
+            Scancode::PageUp => Key::PageUp,
+            Scancode::Copy => Key::Copy,
+            Scancode::Cut => Key::Cut,
+            Scancode::Paste => Key::Paste,
+            Scancode::KpColon => Key::Colon,
+            Scancode::Comma | Scancode::KpComma => Key::Comma,
+            Scancode::Backslash | Scancode::NonUsHash | Scancode::NonUsBackslash => Key::Backslash,
+            Scancode::Slash | Scancode::KpDivide => Key::Slash,
+            Scancode::KpVerticalBar => Key::Pipe,
+            Scancode::KpExclam => Key::Exclamationmark,
+            Scancode::LeftBracket => Key::OpenBracket,
+            Scancode::RightBracket => Key::CloseBracket,
+            Scancode::KpLeftBrace => Key::OpenCurlyBracket,
+            Scancode::KpRightBrace => Key::CloseCurlyBracket,
+            Scancode::Grave => Key::Backtick,
+            Scancode::Minus | Scancode::KpMinus => Key::Minus,
+            Scancode::Period | Scancode::KpPeriod => Key::Period,
+            Scancode::KpPlus => Key::Plus,
+            Scancode::Equals | Scancode::KpEquals => Key::Equals,
+            Scancode::Semicolon => Key::Semicolon,
+            Scancode::Apostrophe => Key::Quote,
+            Scancode::Kp0 | Scancode::Num0 => Key::Num0,
+            Scancode::Kp1 | Scancode::Num1 => Key::Num1,
+            Scancode::Kp2 | Scancode::Num2 => Key::Num2,
+            Scancode::Kp3 | Scancode::Num3 => Key::Num3,
+            Scancode::Kp4 | Scancode::Num4 => Key::Num4,
+            Scancode::Kp5 | Scancode::Num5 => Key::Num5,
+            Scancode::Kp6 | Scancode::Num6 => Key::Num6,
+            Scancode::Kp7 | Scancode::Num7 => Key::Num7,
+            Scancode::Kp8 | Scancode::Num8 => Key::Num8,
+            Scancode::Kp9 | Scancode::Num9 => Key::Num9,
+            Scancode::A => Key::A,
+            Scancode::B => Key::B,
+            Scancode::C => Key::C,
+            Scancode::D => Key::D,
+            Scancode::E => Key::E,
+            Scancode::F => Key::F,
+            Scancode::G => Key::G,
+            Scancode::H => Key::H,
+            Scancode::I => Key::I,
+            Scancode::J => Key::J,
+            Scancode::K => Key::K,
+            Scancode::L => Key::L,
+            Scancode::M => Key::M,
+            Scancode::N => Key::N,
+            Scancode::O => Key::O,
+            Scancode::P => Key::P,
+            Scancode::Q => Key::Q,
+            Scancode::R => Key::R,
+            Scancode::S => Key::S,
+            Scancode::T => Key::T,
+            Scancode::U => Key::U,
+            Scancode::V => Key::V,
+            Scancode::W => Key::W,
+            Scancode::X => Key::X,
+            Scancode::Y => Key::Y,
+            Scancode::Z => Key::Z,
+            Scancode::F1 => Key::F1,
+            Scancode::F2 => Key::F2,
+            Scancode::F3 => Key::F3,
+            Scancode::F4 => Key::F4,
+            Scancode::F5 => Key::F5,
+            Scancode::F6 => Key::F6,
+            Scancode::F7 => Key::F7,
+            Scancode::F8 => Key::F8,
+            Scancode::F9 => Key::F9,
+            Scancode::F10 => Key::F10,
+            Scancode::F11 => Key::F11,
+            Scancode::F12 => Key::F12,
+            Scancode::F13 => Key::F13,
+            Scancode::F14 => Key::F14,
+            Scancode::F15 => Key::F15,
+            Scancode::F16 => Key::F16,
+            Scancode::F17 => Key::F17,
+            Scancode::F18 => Key::F18,
+            Scancode::F19 => Key::F19,
+            Scancode::F20 => Key::F20,
+            Scancode::F21 => Key::F21,
+            Scancode::F22 => Key::F22,
+            Scancode::F23 => Key::F23,
+            Scancode::F24 => Key::F24,
+            Scancode::AcBack => Key::BrowserBack,
+            _ => {
+                return None;
+            }
+        })
+    }
+}
+
+/// A trait that adds a method to convert to an egui pointer button
+pub trait ToEguiPointerButton {
+    /// Convert the struct to an egui pointer button
+    fn to_egui_pointer_button(&self) -> Option<egui::PointerButton>;
+}
+
+impl ToEguiPointerButton for sdl2::mouse::MouseButton {
+    fn to_egui_pointer_button(&self) -> Option<egui::PointerButton> {
+        Some(match *self {
+            MouseButton::Left => PointerButton::Primary,
+            MouseButton::Middle => PointerButton::Middle,
+            MouseButton::Right => PointerButton::Secondary,
+            MouseButton::X1 => PointerButton::Extra1,
+            MouseButton::X2 => PointerButton::Extra2,
+            MouseButton::Unknown => return None,
+        })
+    }
+}
+
+/// A trait that adds a method to convert to an sdl2 system cursor
+pub trait ToSdlSystemCursor {
+    /// Convert the struct to the closest system cursor, `None` when the cursor should be hidden
+    fn to_sdl_system_cursor(&self) -> Option<SystemCursor>;
+}
+
+impl ToSdlSystemCursor for egui::CursorIcon {
+    fn to_sdl_system_cursor(&self) -> Option<SystemCursor> {
+        Some(match *self {
+            CursorIcon::None => return None,
+            CursorIcon::Default
+            | CursorIcon::ContextMenu
+            | CursorIcon::Help
+            | CursorIcon::Alias
+            | CursorIcon::Copy
+            | CursorIcon::ZoomIn
+            | CursorIcon::ZoomOut => SystemCursor::Arrow,
+            CursorIcon::PointingHand | CursorIcon::Grab => SystemCursor::Hand,
+            CursorIcon::Progress => SystemCursor::WaitArrow,
+            CursorIcon::Wait => SystemCursor::Wait,
+            CursorIcon::Cell | CursorIcon::Crosshair => SystemCursor::Crosshair,
+            CursorIcon::Text | CursorIcon::VerticalText => SystemCursor::IBeam,
+            CursorIcon::Move | CursorIcon::Grabbing | CursorIcon::AllScroll => {
+                SystemCursor::SizeAll
+            }
+            CursorIcon::NoDrop | CursorIcon::NotAllowed => SystemCursor::No,
+            CursorIcon::ResizeHorizontal
+            | CursorIcon::ResizeEast
+            | CursorIcon::ResizeWest
+            | CursorIcon::ResizeColumn => SystemCursor::SizeWE,
+            CursorIcon::ResizeVertical
+            | CursorIcon::ResizeNorth
+            | CursorIcon::ResizeSouth
+            | CursorIcon::ResizeRow => SystemCursor::SizeNS,
+            CursorIcon::ResizeNeSw | CursorIcon::ResizeNorthEast | CursorIcon::ResizeSouthWest => {
+                SystemCursor::SizeNESW
+            }
+            CursorIcon::ResizeNwSe | CursorIcon::ResizeNorthWest | CursorIcon::ResizeSouthEast => {
+                SystemCursor::SizeNWSE
+            }
+        })
+    }
+}
+
+/// Decides how the sdl2 modifier state is turned into egui modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModifierPolicy {
+    /// Follow the convention of the platform the crate was compiled for
+    #[default]
+    Native,
+    /// Either ctrl key is the command key, like on Windows and Linux
+    Ctrl,
+    /// Either gui key is the command key and sets `mac_cmd`, like on macOS
+    Mac,
+}
+
+impl ModifierPolicy {
+    /// Convert the sdl2 modifier state to egui modifiers
+    pub fn to_egui_modifiers(&self, keymod: Mod) -> egui::Modifiers {
+        let alt = keymod.intersects(Mod::LALTMOD | Mod::RALTMOD);
+        let ctrl = keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD);
+        let shift = keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+        let gui = keymod.intersects(Mod::LGUIMOD | Mod::RGUIMOD);
+
+        let mac = match self {
+            Self::Native => cfg!(target_os = "macos"),
+            Self::Ctrl => false,
+            Self::Mac => true,
+        };
+        egui::Modifiers {
+            alt,
+            ctrl,
+            shift,
+            mac_cmd: mac && gui,
+            command: if mac { gui } else { ctrl },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The canonical sdl2 keycode for every egui key
+    fn key_table() -> Vec<(Keycode, Key)> {
+        vec![
+            (Keycode::Down, Key::ArrowDown),
+            (Keycode::Left, Key::ArrowLeft),
+            (Keycode::Right, Key::ArrowRight),
+            (Keycode::Up, Key::ArrowUp),
+            (Keycode::Escape, Key::Escape),
+            (Keycode::Tab, Key::Tab),
+            (Keycode::Backspace, Key::Backspace),
+            (Keycode::Return, Key::Enter),
+            (Keycode::Space, Key::Space),
+            (Keycode::Insert, Key::Insert),
+            (Keycode::Delete, Key::Delete),
+            (Keycode::Home, Key::Home),
+            (Keycode::End, Key::End),
+            (Keycode::PageUp, Key::PageUp),
+            (Keycode::PageDown, Key::PageDown),
+            (Keycode::Copy, Key::Copy),
+            (Keycode::Cut, Key::Cut),
+            (Keycode::Paste, Key::Paste),
+            (Keycode::Colon, Key::Colon),
+            (Keycode::Comma, Key::Comma),
+            (Keycode::Backslash, Key::Backslash),
+            (Keycode::Slash, Key::Slash),
+            (Keycode::KpVerticalBar, Key::Pipe),
+            (Keycode::Question, Key::Questionmark),
+            (Keycode::Exclaim, Key::Exclamationmark),
+            (Keycode::LeftBracket, Key::OpenBracket),
+            (Keycode::RightBracket, Key::CloseBracket),
+            (Keycode::KpLeftBrace, Key::OpenCurlyBracket),
+            (Keycode::KpRightBrace, Key::CloseCurlyBracket),
+            (Keycode::Backquote, Key::Backtick),
+            (Keycode::Minus, Key::Minus),
+            (Keycode::Period, Key::Period),
+            (Keycode::Plus, Key::Plus),
+            (Keycode::Equals, Key::Equals),
+            (Keycode::Semicolon, Key::Semicolon),
+            (Keycode::Quote, Key::Quote),
+            (Keycode::A, Key::A),
+            (Keycode::B, Key::B),
+            (Keycode::C, Key::C),
+            (Keycode::D, Key::D),
+            (Keycode::E, Key::E),
+            (Keycode::F, Key::F),
+            (Keycode::G, Key::G),
+            (Keycode::H, Key::H),
+            (Keycode::I, Key::I),
+            (Keycode::J, Key::J),
+            (Keycode::K, Key::K),
+            (Keycode::L, Key::L),
+            (Keycode::M, Key::M),
+            (Keycode::N, Key::N),
+            (Keycode::O, Key::O),
+            (Keycode::P, Key::P),
+            (Keycode::Q, Key::Q),
+            (Keycode::R, Key::R),
+            (Keycode::S, Key::S),
+            (Keycode::T, Key::T),
+            (Keycode::U, Key::U),
+            (Keycode::V, Key::V),
+            (Keycode::W, Key::W),
+            (Keycode::X, Key::X),
+            (Keycode::Y, Key::Y),
+            (Keycode::Z, Key::Z),
+            (Keycode::F1, Key::F1),
+            (Keycode::F2, Key::F2),
+            (Keycode::F3, Key::F3),
+            (Keycode::F4, Key::F4),
+            (Keycode::F5, Key::F5),
+            (Keycode::F6, Key::F6),
+            (Keycode::F7, Key::F7),
+            (Keycode::F8, Key::F8),
+            (Keycode::F9, Key::F9),
+            (Keycode::F10, Key::F10),
+            (Keycode::F11, Key::F11),
+            (Keycode::F12, Key::F12),
+            (Keycode::F13, Key::F13),
+            (Keycode::F14, Key::F14),
+            (Keycode::F15, Key::F15),
+            (Keycode::F16, Key::F16),
+            (Keycode::F17, Key::F17),
+            (Keycode::F18, Key::F18),
+            (Keycode::F19, Key::F19),
+            (Keycode::F20, Key::F20),
+            (Keycode::F21, Key::F21),
+            (Keycode::F22, Key::F22),
+            (Keycode::F23, Key::F23),
+            (Keycode::F24, Key::F24),
+            (Keycode::Num0, Key::Num0),
+            (Keycode::Num1, Key::Num1),
+            (Keycode::Num2, Key::Num2),
+            (Keycode::Num3, Key::Num3),
+            (Keycode::Num4, Key::Num4),
+            (Keycode::Num5, Key::Num5),
+            (Keycode::Num6, Key::Num6),
+            (Keycode::Num7, Key::Num7),
+            (Keycode::Num8, Key::Num8),
+            (Keycode::Num9, Key::Num9),
+            (Keycode::AcBack, Key::BrowserBack),
+        ]
+    }
+
+    #[test]
+    fn keycodes_map_to_egui_keys() {
+        for (keycode, key) in key_table() {
+            assert_eq!(keycode.to_egui_key(), Some(key), "{keycode}");
+        }
+    }
+
+    #[test]
+    fn every_egui_key_is_mapped() {
+        let table = key_table();
+        for key in Key::ALL {
+            // sdl2 has no keycodes past F24
+            if (Key::F25..=Key::F35).contains(key) {
+                continue;
+            }
+            let keycode = table
+                .iter()
+                .find(|(_, k)| k == key)
+                .map(|(keycode, _)| *keycode)
+                .unwrap_or_else(|| panic!("{key:?} has no sdl2 keycode"));
+            assert_eq!(keycode.to_egui_key(), Some(*key));
+        }
+    }
+
+    #[test]
+    fn scancodes_map_to_us_layout_positions() {
+        assert_eq!(Scancode::W.to_egui_physical_key(), Some(Key::W));
+        assert_eq!(Scancode::Grave.to_egui_physical_key(), Some(Key::Backtick));
+        assert_eq!(
+            Scancode::Apostrophe.to_egui_physical_key(),
+            Some(Key::Quote)
+        );
+        assert_eq!(Scancode::Kp5.to_egui_physical_key(), Some(Key::Num5));
+        assert_eq!(Scancode::LShift.to_egui_physical_key(), None);
+    }
+
+    #[test]
+    fn modifier_policies_pick_the_command_key() {
+        let rctrl = Mod::RCTRLMOD;
+        assert!(ModifierPolicy::Ctrl.to_egui_modifiers(rctrl).command);
+        assert!(!ModifierPolicy::Mac.to_egui_modifiers(rctrl).command);
+
+        let lgui = Mod::LGUIMOD | Mod::NUMMOD;
+        assert!(!ModifierPolicy::Ctrl.to_egui_modifiers(lgui).command);
+        let mac = ModifierPolicy::Mac.to_egui_modifiers(lgui);
+        assert!(mac.command && mac.mac_cmd && !mac.ctrl);
+    }
+
+    #[test]
+    fn extra_mouse_buttons_map_to_extra_pointer_buttons() {
+        assert_eq!(
+            MouseButton::X1.to_egui_pointer_button(),
+            Some(PointerButton::Extra1)
+        );
+        assert_eq!(
+            MouseButton::X2.to_egui_pointer_button(),
+            Some(PointerButton::Extra2)
+        );
+        assert_eq!(MouseButton::Unknown.to_egui_pointer_button(), None);
+    }
+
+    #[test]
+    fn keypad_keys_map_to_their_main_counterparts() {
+        assert_eq!(Keycode::KpPlus.to_egui_key(), Some(Key::Plus));
+        assert_eq!(Keycode::KpMinus.to_egui_key(), Some(Key::Minus));
+        assert_eq!(Keycode::KpEnter.to_egui_key(), Some(Key::Enter));
+        assert_eq!(Keycode::KpPeriod.to_egui_key(), Some(Key::Period));
+        assert_eq!(Keycode::LShift.to_egui_key(), None);
+    }
+}
@@ -0,0 +1,294 @@
+use egui::{Color32, ColorImage, CursorIcon};
+use sdl2::{mouse::Cursor, pixels::PixelFormatEnum, surface::Surface};
+
+/// The classic arrow the built-in cursors put their badge next to
+const ARROW: [&str; 17] = [
+    "X          ",
+    "XX         ",
+    "XoX        ",
+    "XooX       ",
+    "XoooX      ",
+    "XooooX     ",
+    "XoooooX    ",
+    "XooooooX   ",
+    "XoooooooX  ",
+    "XooooooooX ",
+    "XoooooXXXXX",
+    "XooXooX    ",
+    "XoX XooX   ",
+    "XX  XooX   ",
+    "X    XooX  ",
+    "     XooX  ",
+    "      XX   ",
+];
+
+/// The badge of the copy cursor
+const COPY_BADGE: [&str; 9] = [
+    "XXXXXXXXX",
+    "XXXXoXXXX",
+    "XXXXoXXXX",
+    "XXXXoXXXX",
+    "XoooooooX",
+    "XXXXoXXXX",
+    "XXXXoXXXX",
+    "XXXXoXXXX",
+    "XXXXXXXXX",
+];
+
+/// The badge of the alias cursor
+const ALIAS_BADGE: [&str; 9] = [
+    "XXXXXXXXX",
+    "XXXXooooX",
+    "XXXXXXooX",
+    "XXXXXoXoX",
+    "XXXXoXXoX",
+    "XXXoXXXXX",
+    "XXoXXXXXX",
+    "XoXXXXXXX",
+    "XXXXXXXXX",
+];
+
+/// The badge of the help cursor
+const HELP_BADGE: [&str; 9] = [
+    "XXXXXXXXX",
+    "XXXoooXXX",
+    "XXoXXXoXX",
+    "XXXXXXoXX",
+    "XXXXXoXXX",
+    "XXXXoXXXX",
+    "XXXXXXXXX",
+    "XXXXoXXXX",
+    "XXXXXXXXX",
+];
+
+/// The badge of the context menu cursor
+const CONTEXT_MENU_BADGE: [&str; 9] = [
+    "XXXXXXXXX",
+    "XoooooooX",
+    "XXXXXXXXX",
+    "XoooooooX",
+    "XXXXXXXXX",
+    "XoooooooX",
+    "XXXXXXXXX",
+    "XoooooooX",
+    "XXXXXXXXX",
+];
+
+/// The cell cursor, a thick plus
+const CELL: [&str; 15] = [
+    "     XXXXX     ",
+    "     XoooX     ",
+    "     XoooX     ",
+    "     XoooX     ",
+    "     XoooX     ",
+    "XXXXXXoooXXXXXX",
+    "XoooooooooooooX",
+    "XoooooooooooooX",
+    "XoooooooooooooX",
+    "XXXXXXoooXXXXXX",
+    "     XoooX     ",
+    "     XoooX     ",
+    "     XoooX     ",
+    "     XoooX     ",
+    "     XXXXX     ",
+];
+
+/// An image used as cursor
+#[derive(Debug, Clone, PartialEq)]
+pub struct CursorImage {
+    /// The cursor image
+    pub image: ColorImage,
+    /// The pixel of the image that points at the cursor position
+    pub hotspot: [usize; 2],
+}
+
+impl CursorImage {
+    /// Construct a new [`CursorImage`]
+    pub fn new(image: ColorImage, hotspot: [usize; 2]) -> Self {
+        Self { image, hotspot }
+    }
+
+    /// Build an sdl2 cursor from the image, scaled up by a whole factor
+    pub fn to_sdl_cursor(&self, scale: u32) -> anyhow::Result<Cursor> {
+        let scale = scale.max(1) as usize;
+        let [width, height] = [self.image.size[0] * scale, self.image.size[1] * scale];
+
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = self.image.pixels[(y / scale) * self.image.size[0] + x / scale];
+                pixels.extend_from_slice(&pixel.to_srgba_unmultiplied());
+            }
+        }
+
+        let surface = Surface::from_data(
+            &mut pixels,
+            width as u32,
+            height as u32,
+            width as u32 * 4,
+            PixelFormatEnum::RGBA32,
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to create cursor surface: {}", e))?;
+        Cursor::from_surface(
+            surface,
+            (self.hotspot[0] * scale) as i32,
+            (self.hotspot[1] * scale) as i32,
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to create cursor from surface: {}", e))
+    }
+}
+
+/// Maps egui cursor icons to image cursors, for icons sdl2 has no system cursor for
+#[derive(Debug, Clone, PartialEq)]
+pub struct CursorTheme {
+    /// The icons and the images shown for them
+    pub images: Vec<(CursorIcon, CursorImage)>,
+    /// Whether the images are scaled up with the pixels per point of the display, ignoring the ui zoom
+    pub scale_with_dpi: bool,
+}
+
+impl CursorTheme {
+    /// Construct a theme without any images, only system cursors will be used
+    pub fn empty() -> Self {
+        Self {
+            images: Vec::new(),
+            scale_with_dpi: false,
+        }
+    }
+
+    /// Construct a theme with the built-in images for the icons sdl2 has no system cursor for
+    pub fn builtin() -> Self {
+        Self::empty()
+            .with(CursorIcon::Copy, badged_arrow(&COPY_BADGE))
+            .with(CursorIcon::Alias, badged_arrow(&ALIAS_BADGE))
+            .with(CursorIcon::Help, badged_arrow(&HELP_BADGE))
+            .with(CursorIcon::ContextMenu, badged_arrow(&CONTEXT_MENU_BADGE))
+            .with(CursorIcon::Cell, CursorImage::new(bitmap(&CELL), [7, 7]))
+            .with(CursorIcon::ZoomIn, magnifier(true))
+            .with(CursorIcon::ZoomOut, magnifier(false))
+    }
+
+    /// Set the image shown for an icon, replacing the previous one
+    pub fn with(mut self, icon: CursorIcon, image: CursorImage) -> Self {
+        self.set(icon, image);
+        self
+    }
+
+    /// Set the image shown for an icon, replacing the previous one
+    pub fn set(&mut self, icon: CursorIcon, image: CursorImage) {
+        self.images.retain(|(i, _)| *i != icon);
+        self.images.push((icon, image));
+    }
+
+    /// Get the image shown for an icon
+    pub fn get(&self, icon: CursorIcon) -> Option<&CursorImage> {
+        self.images
+            .iter()
+            .find(|(i, _)| *i == icon)
+            .map(|(_, image)| image)
+    }
+}
+
+impl Default for CursorTheme {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// Turn ascii art into an image, `X` is black, `o` is white and anything else is transparent
+fn bitmap(rows: &[&str]) -> ColorImage {
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut image = ColorImage::new(
+        [width, rows.len()],
+        vec![Color32::TRANSPARENT; width * rows.len()],
+    );
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            image[(x, y)] = match c {
+                'X' => Color32::BLACK,
+                'o' => Color32::WHITE,
+                _ => Color32::TRANSPARENT,
+            };
+        }
+    }
+    image
+}
+
+/// The arrow with a badge below its right side
+fn badged_arrow(badge: &[&str]) -> CursorImage {
+    let arrow = bitmap(&ARROW);
+    let badge = bitmap(badge);
+    let offset = [arrow.size[0], arrow.size[1] - badge.size[1] + 3];
+
+    let size = [offset[0] + badge.size[0], offset[1] + badge.size[1]];
+    let mut image = ColorImage::new(size, vec![Color32::TRANSPARENT; size[0] * size[1]]);
+    for (src, [ox, oy]) in [(&arrow, [0, 0]), (&badge, offset)] {
+        for y in 0..src.size[1] {
+            for x in 0..src.size[0] {
+                image[(ox + x, oy + y)] = src[(x, y)];
+            }
+        }
+    }
+    CursorImage::new(image, [0, 0])
+}
+
+/// A magnifying glass with a plus or minus in its lens
+fn magnifier(plus: bool) -> CursorImage {
+    const SIZE: usize = 20;
+    const CENTER: f32 = 7.5;
+    let mut image = ColorImage::new([SIZE, SIZE], vec![Color32::TRANSPARENT; SIZE * SIZE]);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let (dx, dy) = (x as f32 - CENTER, y as f32 - CENTER);
+            let distance = (dx * dx + dy * dy).sqrt();
+            let on_handle = x == y || x == y + 1 || y == x + 1;
+            let on_sign = (y == 7 || y == 8) && (4..=11).contains(&x)
+                || plus && (x == 7 || x == 8) && (4..=11).contains(&y);
+
+            image[(x, y)] = if distance < 5.5 {
+                if on_sign {
+                    Color32::BLACK
+                } else {
+                    Color32::WHITE
+                }
+            } else if distance < 7.5 || on_handle && x >= 12 {
+                Color32::BLACK
+            } else {
+                Color32::TRANSPARENT
+            };
+        }
+    }
+    CursorImage::new(image, [7, 7])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_theme_covers_icons_without_system_cursor() {
+        let theme = CursorTheme::builtin();
+        for icon in [
+            CursorIcon::ZoomIn,
+            CursorIcon::ZoomOut,
+            CursorIcon::Help,
+            CursorIcon::ContextMenu,
+            CursorIcon::Copy,
+            CursorIcon::Alias,
+            CursorIcon::Cell,
+        ] {
+            let cursor = theme.get(icon).unwrap();
+            let [width, height] = cursor.image.size;
+            assert!(cursor.hotspot[0] < width && cursor.hotspot[1] < height);
+        }
+        assert!(theme.get(CursorIcon::Default).is_none());
+    }
+
+    #[test]
+    fn setting_an_icon_replaces_its_image() {
+        let image = CursorImage::new(bitmap(&["X"]), [0, 0]);
+        let theme = CursorTheme::builtin().with(CursorIcon::Copy, image.clone());
+        assert_eq!(theme.get(CursorIcon::Copy), Some(&image));
+        assert_eq!(theme.images.len(), CursorTheme::builtin().images.len());
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::{Path, PathBuf};
+
+/// Guess the mime type of a file from its extension, `application/octet-stream` when unknown
+pub fn guess_mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("txt" | "log") => "text/plain",
+        Some("md") => "text/markdown",
+        Some("csv") => "text/csv",
+        Some("html" | "htm") => "text/html",
+        Some("css") => "text/css",
+        Some("js") => "text/javascript",
+        Some("json") => "application/json",
+        Some("xml") => "application/xml",
+        Some("toml") => "application/toml",
+        Some("yaml" | "yml") => "application/yaml",
+        Some("pdf") => "application/pdf",
+        Some("zip") => "application/zip",
+        Some("gz") => "application/gzip",
+        Some("tar") => "application/x-tar",
+        Some("wasm") => "application/wasm",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("bmp") => "image/bmp",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/vnd.microsoft.icon",
+        Some("tga") => "image/x-tga",
+        Some("ktx2") => "image/ktx2",
+        Some("wav") => "audio/wav",
+        Some("mp3") => "audio/mpeg",
+        Some("ogg") => "audio/ogg",
+        Some("flac") => "audio/flac",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        Some("ttf") => "font/ttf",
+        Some("otf") => "font/otf",
+        Some("woff2") => "font/woff2",
+        Some("gltf") => "model/gltf+json",
+        Some("glb") => "model/gltf-binary",
+        Some("obj") => "model/obj",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Describe a file dropped on the window by its path
+pub(crate) fn dropped_file(path: PathBuf) -> egui::DroppedFile {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let last_modified = std::fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    egui::DroppedFile {
+        mime: guess_mime_type(&path).to_owned(),
+        name,
+        last_modified,
+        path: Some(path),
+        bytes: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mime_type_ignores_extension_case() {
+        assert_eq!(guess_mime_type(Path::new("a/b/Photo.JPG")), "image/jpeg");
+        assert_eq!(guess_mime_type(Path::new("scene.glb")), "model/gltf-binary");
+        assert_eq!(
+            guess_mime_type(Path::new("Makefile")),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn dropped_file_is_named_after_the_path() {
+        let file = dropped_file(PathBuf::from("/does/not/exist/level.json"));
+        assert_eq!(file.name, "level.json");
+        assert_eq!(file.mime, "application/json");
+        assert_eq!(file.last_modified, None);
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    ffi::c_void,
+    sync::{Arc, Mutex},
+};
+
+use egui::{CursorIcon, Pos2, Rect, ResizeDirection, Vec2};
+use sdl2::sys::{SDL_HitTestResult, SDL_Point, SDL_Window};
+
+/// The parts of a borderless window the window manager moves and resizes it by
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct HitTestAreas {
+    /// The areas the window is dragged by, in window units
+    pub drag_areas: Vec<Rect>,
+    /// The interactive widgets over the drag areas, which keep their presses, in window units
+    pub interactive_areas: Vec<Rect>,
+    /// The width of the edges the window is resized by, in window units
+    pub border_width: f32,
+    /// The size of the window, in window units
+    pub window_size: Vec2,
+    /// Whether the window can be resized at the moment
+    pub resizable: bool,
+}
+
+impl HitTestAreas {
+    /// Find the edge of the window a position resizes
+    pub fn resize_direction(&self, pos: Pos2) -> Option<ResizeDirection> {
+        if !self.resizable || self.border_width <= 0.0 {
+            return None;
+        }
+        let west = pos.x < self.border_width;
+        let east = pos.x >= self.window_size.x - self.border_width;
+        let north = pos.y < self.border_width;
+        let south = pos.y >= self.window_size.y - self.border_width;
+        match (north, south, west, east) {
+            (true, _, true, _) => Some(ResizeDirection::NorthWest),
+            (true, _, _, true) => Some(ResizeDirection::NorthEast),
+            (_, true, true, _) => Some(ResizeDirection::SouthWest),
+            (_, true, _, true) => Some(ResizeDirection::SouthEast),
+            (true, _, _, _) => Some(ResizeDirection::North),
+            (_, true, _, _) => Some(ResizeDirection::South),
+            (_, _, true, _) => Some(ResizeDirection::West),
+            (_, _, _, true) => Some(ResizeDirection::East),
+            _ => None,
+        }
+    }
+
+    /// Decide what the window manager does with a press at a position
+    pub fn hit_test(&self, pos: Pos2) -> SDL_HitTestResult {
+        if let Some(direction) = self.resize_direction(pos) {
+            return match direction {
+                ResizeDirection::North => SDL_HitTestResult::SDL_HITTEST_RESIZE_TOP,
+                ResizeDirection::South => SDL_HitTestResult::SDL_HITTEST_RESIZE_BOTTOM,
+                ResizeDirection::West => SDL_HitTestResult::SDL_HITTEST_RESIZE_LEFT,
+                ResizeDirection::East => SDL_HitTestResult::SDL_HITTEST_RESIZE_RIGHT,
+                ResizeDirection::NorthWest => SDL_HitTestResult::SDL_HITTEST_RESIZE_TOPLEFT,
+                ResizeDirection::NorthEast => SDL_HitTestResult::SDL_HITTEST_RESIZE_TOPRIGHT,
+                ResizeDirection::SouthWest => SDL_HitTestResult::SDL_HITTEST_RESIZE_BOTTOMLEFT,
+                ResizeDirection::SouthEast => SDL_HitTestResult::SDL_HITTEST_RESIZE_BOTTOMRIGHT,
+            };
+        }
+        if self.drag_areas.iter().any(|area| area.contains(pos))
+            && !self.interactive_areas.iter().any(|area| area.contains(pos))
+        {
+            return SDL_HitTestResult::SDL_HITTEST_DRAGGABLE;
+        }
+        SDL_HitTestResult::SDL_HITTEST_NORMAL
+    }
+}
+
+/// The cursor icon shown over an edge the window is resized by
+pub(crate) fn resize_cursor_icon(direction: ResizeDirection) -> CursorIcon {
+    match direction {
+        ResizeDirection::North => CursorIcon::ResizeNorth,
+        ResizeDirection::South => CursorIcon::ResizeSouth,
+        ResizeDirection::West => CursorIcon::ResizeWest,
+        ResizeDirection::East => CursorIcon::ResizeEast,
+        ResizeDirection::NorthWest => CursorIcon::ResizeNorthWest,
+        ResizeDirection::NorthEast => CursorIcon::ResizeNorthEast,
+        ResizeDirection::SouthWest => CursorIcon::ResizeSouthWest,
+        ResizeDirection::SouthEast => CursorIcon::ResizeSouthEast,
+    }
+}
+
+/// Called by sdl whenever it needs to know what a part of the window does
+unsafe extern "C" fn hit_test_callback(
+    _window: *mut SDL_Window,
+    area: *const SDL_Point,
+    data: *mut c_void,
+) -> SDL_HitTestResult {
+    // The areas are kept alive by the reference `set_window_hit_test` handed out
+    let areas = unsafe { &*(data as *const Mutex<HitTestAreas>) };
+    let area = unsafe { &*area };
+    match areas.lock() {
+        Ok(areas) => areas.hit_test(Pos2::new(area.x as f32, area.y as f32)),
+        Err(_) => SDL_HitTestResult::SDL_HITTEST_NORMAL,
+    }
+}
+
+/// Install the hit test of the areas on the window, or remove it with `None`
+///
+/// `installed` holds the reference the installed callback uses. The window may outlive
+/// the areas' other owners, so it is only released once the callback is replaced.
+pub(crate) fn set_window_hit_test(
+    window: &sdl2::video::Window,
+    areas: Option<&Arc<Mutex<HitTestAreas>>>,
+    installed: &mut Option<*const Mutex<HitTestAreas>>,
+) -> anyhow::Result<()> {
+    let data = areas.map(|areas| Arc::into_raw(areas.clone()));
+    let result = unsafe {
+        match data {
+            Some(data) => sdl2::sys::SDL_SetWindowHitTest(
+                window.raw(),
+                Some(hit_test_callback),
+                data as *mut c_void,
+            ),
+            None => sdl2::sys::SDL_SetWindowHitTest(window.raw(), None, std::ptr::null_mut()),
+        }
+    };
+    if result != 0 {
+        // The old callback stays installed, so only the new reference is released
+        if let Some(data) = data {
+            drop(unsafe { Arc::from_raw(data) });
+        }
+        return Err(anyhow::anyhow!(
+            "Failed to set the window hit test: {}",
+            sdl2::get_error()
+        ));
+    }
+    // Sdl no longer calls the old callback
+    if let Some(old) = std::mem::replace(installed, data) {
+        drop(unsafe { Arc::from_raw(old) });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn areas() -> HitTestAreas {
+        HitTestAreas {
+            drag_areas: vec![Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 30.0))],
+            interactive_areas: Vec::new(),
+            border_width: 4.0,
+            window_size: Vec2::new(800.0, 600.0),
+            resizable: true,
+        }
+    }
+
+    #[test]
+    fn edges_resize_before_drag_areas() {
+        let areas = areas();
+        assert!(
+            areas.hit_test(Pos2::new(1.0, 1.0)) == SDL_HitTestResult::SDL_HITTEST_RESIZE_TOPLEFT
+        );
+        assert!(areas.hit_test(Pos2::new(400.0, 2.0)) == SDL_HitTestResult::SDL_HITTEST_RESIZE_TOP);
+        assert!(
+            areas.hit_test(Pos2::new(799.0, 300.0)) == SDL_HitTestResult::SDL_HITTEST_RESIZE_RIGHT
+        );
+        assert!(areas.hit_test(Pos2::new(400.0, 15.0)) == SDL_HitTestResult::SDL_HITTEST_DRAGGABLE);
+        assert!(areas.hit_test(Pos2::new(400.0, 300.0)) == SDL_HitTestResult::SDL_HITTEST_NORMAL);
+    }
+
+    #[test]
+    fn fixed_size_windows_have_no_edges() {
+        let areas = HitTestAreas {
+            resizable: false,
+            ..areas()
+        };
+        assert_eq!(areas.resize_direction(Pos2::new(1.0, 1.0)), None);
+        assert!(areas.hit_test(Pos2::new(1.0, 1.0)) == SDL_HitTestResult::SDL_HITTEST_DRAGGABLE);
+    }
+
+    #[test]
+    fn interactive_widgets_keep_their_presses() {
+        let areas = HitTestAreas {
+            interactive_areas: vec![Rect::from_min_size(
+                Pos2::new(760.0, 5.0),
+                Vec2::new(20.0, 20.0),
+            )],
+            ..areas()
+        };
+        assert!(areas.hit_test(Pos2::new(770.0, 15.0)) == SDL_HitTestResult::SDL_HITTEST_NORMAL);
+        assert!(areas.hit_test(Pos2::new(400.0, 15.0)) == SDL_HitTestResult::SDL_HITTEST_DRAGGABLE);
+    }
+}
@@ -1,9 +1,18 @@
 //! An graphics-backend independant egui backend for sdl2
+pub mod clipboard;
 pub mod conversions;
+pub mod cursor;
+pub mod drop;
+mod hit_test;
 pub mod platform;
+pub mod touch;
 
+pub use crate::clipboard::*;
 pub use crate::conversions::*;
+pub use crate::cursor::*;
+pub use crate::drop::*;
 pub use crate::platform::*;
+pub use crate::touch::*;
 
 /// SDL2 is re-exported to enable easier version sync for users
 pub use sdl2;
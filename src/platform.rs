@@ -1,77 +1,414 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
 use egui::{Modifiers, Pos2};
 use sdl2::{
     event::{Event, WindowEvent},
-    mouse::{Cursor, MouseButton, SystemCursor},
+    keyboard::{Keycode, Scancode},
+    messagebox::{
+        show_message_box, ButtonData, ClickedButton, MessageBoxButtonFlag, MessageBoxFlag,
+    },
+    mouse::{Cursor, SystemCursor},
+    pixels::PixelFormatEnum,
+    surface::Surface,
+    sys::SDL_EventType,
+    video::{FlashOperation, FullscreenType, WindowPos},
 };
 
-use crate::ToEguiKey;
+use crate::{
+    clipboard::selected_text,
+    drop::dropped_file,
+    hit_test::{resize_cursor_icon, set_window_hit_test, HitTestAreas},
+    touch::TouchGestures,
+    ClipboardAction, ClipboardProvider, ClipboardShortcuts, CopyImageFallback, CursorTheme,
+    ModifierPolicy, SdlClipboard, ToEguiKey, ToEguiPhysicalKey, ToEguiPointerButton,
+    ToSdlSystemCursor, TouchMode,
+};
+
+/// The mouse id sdl gives the mouse events it makes from touches
+const SDL_TOUCH_MOUSEID: u32 = u32::MAX;
+/// How fast pinching with multiple fingers zooms
+const PINCH_ZOOM_SPEED: f32 = 2.0;
+
+/// The display dpi at which one point is one pixel on windows
+#[cfg(windows)]
+const DEFAULT_DPI: f32 = 96.0;
+
+/// Opens the urls egui asks for, e.g. when a hyperlink is clicked
+pub type UrlHandler = Box<dyn FnMut(&egui::OpenUrl) -> anyhow::Result<()>>;
+
+/// Open a url with the default system handler through sdl2.
+///
+/// Sdl2 leaves it to the browser whether a new tab is used, so `new_tab` is only
+/// honored by custom [`UrlHandler`]s.
+pub fn open_url_with_sdl(open_url: &egui::OpenUrl) -> anyhow::Result<()> {
+    sdl2::url::open_url(&open_url.url).map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+/// What egui did with an event given to [`Platform::handle_event`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventResponse {
+    /// Whether egui uses the event, e.g. a click on a window or a key typed into a text field.
+    /// Consumed events should not be handled by the rest of the application.
+    pub consumed: bool,
+    /// Whether egui has to be redrawn to show the effect of the event
+    pub repaint: bool,
+}
+
+/// The native dialog that confirms closing the window when egui did not cancel it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloseConfirmation {
+    /// The title of the dialog
+    pub title: String,
+    /// The question the dialog asks
+    pub message: String,
+    /// The label of the button that closes the window
+    pub confirm: String,
+    /// The label of the button that keeps the window open
+    pub cancel: String,
+}
+
+impl Default for CloseConfirmation {
+    fn default() -> Self {
+        Self {
+            title: "Quit".to_owned(),
+            message: "Do you really want to quit?".to_owned(),
+            confirm: "Quit".to_owned(),
+            cancel: "Cancel".to_owned(),
+        }
+    }
+}
 
 /// The sdl2 platform for egui
 pub struct Platform {
-    // The cursors for the platform
-    cursor: Option<Cursor>,
-    system_cursor: SystemCursor,
+    // The system cursors, created once when first used
+    system_cursors: HashMap<SystemCursor, Cursor>,
+    // The images shown for icons without a system cursor
+    cursor_theme: CursorTheme,
+    // The cursors built from the theme images
+    image_cursors: Vec<(egui::CursorIcon, Cursor)>,
+    // The scale the image cursors were built at
+    image_cursor_scale: u32,
+    // The cursor icon that is currently shown, `None` when it has to be refreshed
+    cursor_icon: Option<egui::CursorIcon>,
+    // Whether the platform sets the cursor egui asks for
+    manage_cursor: bool,
     // The position of the mouse pointer
     pointer_pos: Pos2,
+    // The size of the window in window units, the unit of sdl window and mouse coordinates
+    window_size: egui::Vec2,
+    // The id of the window egui draws in, `None` until the window is first updated
+    window_id: Option<u32>,
+    // The pixels of the drawable per window unit
+    pixels_per_window_unit: f32,
+    // The pixels per point of the display the window is on
+    native_pixels_per_point: f32,
+    // Whether the display scale has to be queried again
+    scale_outdated: bool,
+    // The usable area of the display the window is on, in points
+    usable_display_rect: Option<egui::Rect>,
     // The egui modifiers
     modifiers: Modifiers,
+    // Decides how the sdl modifiers become egui modifiers
+    modifier_policy: ModifierPolicy,
+    // The shortcuts that trigger copy, cut and paste
+    clipboard_shortcuts: ClipboardShortcuts,
+    // The clipboard, the sdl clipboard is used until one is set
+    clipboard: Option<Box<dyn ClipboardProvider>>,
+    // Handles copied images the clipboard could not take
+    copy_image_fallback: Option<CopyImageFallback>,
+    // Opens the urls egui asks for
+    url_handler: UrlHandler,
+    // The touch device and finger that drives the pointer
+    pointer_touch: Option<(i64, i64)>,
+    // The touch-first interaction mode, off when `None`
+    touch_mode: Option<TouchMode>,
+    // The gestures of the finger driving the pointer in touch mode
+    touch_gestures: TouchGestures,
+    // Whether touch was used since the mouse last moved
+    touch_in_use: bool,
+    // Whether an input method is composing text
+    ime_composing: bool,
+    // Whether the platform started the text input, and so may stop it
+    started_text_input: bool,
+    // The input method area last given to sdl, in window units
+    ime_rect: Option<egui::Rect>,
+    // The primary selection a middle click may paste, and where the click was
+    primary_paste: Option<(String, Pos2)>,
+    // The middle click paste whose click egui sees during the current pass
+    primary_paste_in_pass: Option<(String, Pos2)>,
+    // The egui keys sent for the currently held scancodes
+    pressed_keys: HashMap<Scancode, egui::Key>,
+    // The currently held pointer buttons
+    pressed_buttons: Vec<egui::PointerButton>,
+    // Whether files are being dropped on the window
+    dropping: bool,
+    // The files dropped so far, handed to egui once the drop completes
+    dropped_files: Vec<egui::DroppedFile>,
+    // Whether the mouse is captured to keep dragging outside the window
+    mouse_captured: bool,
+    // Whether the mouse is over the window
+    mouse_in_window: bool,
+    // How egui asked for the cursor to be grabbed
+    cursor_grab: egui::CursorGrab,
+    // Whether egui asked for the cursor to be visible
+    cursor_visible: bool,
+    // Whether the mouse is in relative mode, where the pointer stays put
+    relative_mouse: bool,
+    // The width of the edges a borderless window is resized by, hit testing is off when `None`
+    hit_test_border: Option<f32>,
+    // The areas the installed hit test reads, released once it is removed
+    hit_test_data: Option<*const Mutex<HitTestAreas>>,
+    // The parts of the window the window manager moves and resizes it by, shared with the hit test
+    hit_test_areas: Arc<Mutex<HitTestAreas>>,
+    // The areas the window is dragged by this frame, in points
+    drag_areas: Vec<egui::Rect>,
+    // The widgets that asked to drag the window, which move it from their next press
+    drag_widgets: Vec<egui::Id>,
+    // Whether closing was requested since the last pass began
+    close_requested: bool,
+    // Whether closing was requested when the current pass began
+    close_requested_in_pass: bool,
+    // Whether the application should close
+    should_close: bool,
+    // The native dialog asked when egui did not cancel closing, `None` closes right away
+    close_confirmation: Option<CloseConfirmation>,
+    // Whether the window is minimized
+    minimized: bool,
+    // Whether the window is hidden
+    hidden: bool,
     // The raw input
     raw_input: egui::RawInput,
+    // The points scrolled per wheel line, `None` reports lines to egui
+    scroll_speed: Option<f32>,
 
     // The egui context
     egui_ctx: egui::Context,
 }
 
 impl Platform {
-    /// Construct a new [`Platform`]
+    /// Construct a new [`Platform`] for a window of the given size.
+    ///
+    /// Until [`Platform::update_window`] is called one point is one window unit.
     pub fn new(screen_size: (u32, u32)) -> anyhow::Result<Self> {
         Ok(Self {
-            cursor: Cursor::from_system(SystemCursor::Arrow)
-                .map_err(|e| log::warn!("Failed to get cursor from systems cursor: {}", e))
-                .ok(),
-            system_cursor: SystemCursor::Arrow,
+            system_cursors: HashMap::new(),
+            cursor_theme: CursorTheme::default(),
+            image_cursors: Vec::new(),
+            image_cursor_scale: 1,
+            cursor_icon: Some(egui::CursorIcon::Default),
+            manage_cursor: true,
             pointer_pos: Pos2::ZERO,
-            raw_input: egui::RawInput {
-                screen_rect: Some(egui::Rect::from_min_size(
-                    egui::Pos2::ZERO,
-                    egui::Vec2 {
-                        x: screen_size.0 as f32,
-                        y: screen_size.1 as f32,
-                    },
-                )),
-                ..Default::default()
-            },
+            window_size: egui::Vec2::new(screen_size.0 as f32, screen_size.1 as f32),
+            window_id: None,
+            pixels_per_window_unit: 1.0,
+            native_pixels_per_point: 1.0,
+            scale_outdated: true,
+            usable_display_rect: None,
+            raw_input: egui::RawInput::default(),
             modifiers: Modifiers::default(),
+            modifier_policy: ModifierPolicy::default(),
+            clipboard_shortcuts: ClipboardShortcuts::default(),
+            clipboard: None,
+            copy_image_fallback: None,
+            url_handler: Box::new(open_url_with_sdl),
+            pointer_touch: None,
+            touch_mode: None,
+            touch_gestures: TouchGestures::default(),
+            touch_in_use: false,
+            ime_composing: false,
+            started_text_input: false,
+            ime_rect: None,
+            primary_paste: None,
+            primary_paste_in_pass: None,
+            pressed_keys: HashMap::new(),
+            pressed_buttons: Vec::new(),
+            mouse_captured: false,
+            mouse_in_window: true,
+            cursor_grab: egui::CursorGrab::None,
+            cursor_visible: true,
+            relative_mouse: false,
+            dropping: false,
+            dropped_files: Vec::new(),
+            hit_test_border: None,
+            hit_test_data: None,
+            hit_test_areas: Arc::new(Mutex::new(HitTestAreas::default())),
+            drag_areas: Vec::new(),
+            drag_widgets: Vec::new(),
+            close_requested: false,
+            close_requested_in_pass: false,
+            should_close: false,
+            close_confirmation: None,
+            minimized: false,
+            hidden: false,
+            scroll_speed: None,
             egui_ctx: egui::Context::default(),
         })
     }
 
-    /// Handle a sdl2 event
-    pub fn handle_event(&mut self, event: &Event, sdl: &sdl2::Sdl, video: &sdl2::VideoSubsystem) {
+    /// Handle a sdl2 event, returning whether egui consumed it
+    pub fn handle_event(
+        &mut self,
+        event: &Event,
+        sdl: &sdl2::Sdl,
+        video: &sdl2::VideoSubsystem,
+    ) -> EventResponse {
+        // Egui decided what it wants during the previous pass
+        let response = self.event_response(event);
+
+        // Touches are handled from the finger events, skip the mouse events sdl makes from them
+        if let Event::MouseMotion { which, .. }
+        | Event::MouseButtonDown { which, .. }
+        | Event::MouseButtonUp { which, .. }
+        | Event::MouseWheel { which, .. } = event
+        {
+            if *which == SDL_TOUCH_MOUSEID {
+                return EventResponse {
+                    repaint: false,
+                    ..response
+                };
+            }
+        }
+
+        // Keep the modifiers in sync with sdl, even for keys egui does not know
+        if matches!(
+            event,
+            Event::KeyDown { .. }
+                | Event::KeyUp { .. }
+                | Event::MouseButtonDown { .. }
+                | Event::MouseButtonUp { .. }
+                | Event::MouseMotion { .. }
+                | Event::MouseWheel { .. }
+                | Event::Window {
+                    win_event: WindowEvent::FocusGained | WindowEvent::FocusLost,
+                    ..
+                }
+        ) {
+            self.modifiers = self
+                .modifier_policy
+                .to_egui_modifiers(sdl.keyboard().mod_state());
+            self.raw_input.modifiers = self.modifiers;
+        }
+
         match event {
             // Handle reizing
-            Event::Window { win_event, .. } => match win_event {
-                WindowEvent::Resized(w, h) | WindowEvent::SizeChanged(w, h) => {
-                    self.raw_input.screen_rect = Some(egui::Rect::from_min_size(
-                        egui::Pos2::ZERO,
-                        egui::Vec2 {
-                            x: *w as f32,
-                            y: *h as f32,
-                        },
-                    ));
+            Event::Window {
+                win_event: WindowEvent::Resized(w, h) | WindowEvent::SizeChanged(w, h),
+                ..
+            } => {
+                self.window_size = egui::Vec2::new(*w as f32, *h as f32);
+            }
+            // The new display may have a different scale
+            Event::Window {
+                win_event: WindowEvent::DisplayChanged(_),
+                ..
+            } => {
+                self.scale_outdated = true;
+                self.egui_ctx.request_repaint();
+            }
+
+            // Let egui decide whether to close, it can cancel during the next pass
+            Event::Window {
+                win_event: WindowEvent::Close,
+                window_id,
+                ..
+            } if self.window_id.is_none_or(|id| id == *window_id) => {
+                self.request_close();
+            }
+            Event::Quit { .. } => {
+                self.request_close();
+            }
+
+            // Handle the window gaining focus
+            Event::Window {
+                win_event: WindowEvent::FocusGained,
+                ..
+            } => {
+                self.raw_input.focused = true;
+                self.raw_input.events.push(egui::Event::WindowFocused(true));
+            }
+            // Release everything that is held, egui will never see the real release
+            Event::Window {
+                win_event: WindowEvent::FocusLost,
+                ..
+            } => {
+                self.release_held_keys();
+                self.release_held_buttons();
+                self.release_pointer_touch();
+                self.raw_input.events.push(egui::Event::PointerGone);
+                self.raw_input.focused = false;
+                self.raw_input
+                    .events
+                    .push(egui::Event::WindowFocused(false));
+            }
+            Event::Window {
+                win_event: WindowEvent::Enter,
+                ..
+            } => self.mouse_in_window = true,
+            // The pointer is gone once the mouse leaves the window, unless a drag keeps it captured
+            Event::Window {
+                win_event: WindowEvent::Leave,
+                ..
+            } => {
+                self.mouse_in_window = false;
+                if !self.mouse_captured {
+                    self.release_held_buttons();
+                    self.raw_input.events.push(egui::Event::PointerGone);
                 }
-                _ => {}
-            },
+            }
+
+            // Track whether the window can be seen
+            Event::Window {
+                win_event: WindowEvent::Minimized,
+                ..
+            } => self.minimized = true,
+            Event::Window {
+                win_event: WindowEvent::Restored | WindowEvent::Maximized,
+                ..
+            } => {
+                self.minimized = false;
+                self.egui_ctx.request_repaint();
+            }
+            Event::Window {
+                win_event: WindowEvent::Hidden,
+                ..
+            } => self.hidden = true,
+            Event::Window {
+                win_event: WindowEvent::Shown,
+                ..
+            } => {
+                self.hidden = false;
+                self.egui_ctx.request_repaint();
+            }
+            // The window contents were lost and have to be drawn again
+            Event::Window {
+                win_event: WindowEvent::Exposed,
+                ..
+            } => self.egui_ctx.request_repaint(),
 
             // Handle the mouse button being held down
-            Event::MouseButtonDown { mouse_btn, .. } => {
-                let btn = match mouse_btn {
-                    MouseButton::Left => Some(egui::PointerButton::Primary),
-                    MouseButton::Middle => Some(egui::PointerButton::Middle),
-                    MouseButton::Right => Some(egui::PointerButton::Secondary),
-                    _ => None,
-                };
+            Event::MouseButtonDown {
+                mouse_btn, x, y, ..
+            } => {
+                // Egui counts double and triple clicks itself, so `clicks` is not forwarded
+                self.move_pointer_to(*x, *y);
+                let btn = mouse_btn.to_egui_pointer_button();
                 if let Some(btn) = btn {
+                    if !self.pressed_buttons.contains(&btn) {
+                        self.pressed_buttons.push(btn);
+                    }
+                    // Middle click pastes the primary selection, once egui shows a text edit was clicked
+                    if btn == egui::PointerButton::Middle {
+                        match self.clipboard(video).primary_selection() {
+                            Ok(Some(text)) => self.primary_paste = Some((text, self.pointer_pos)),
+                            Ok(None) => {}
+                            Err(e) => log::warn!("{}", e),
+                        }
+                    }
                     self.raw_input.events.push(egui::Event::PointerButton {
                         pos: self.pointer_pos,
                         button: btn,
@@ -79,17 +416,15 @@ impl Platform {
                         modifiers: self.modifiers,
                     });
                 }
-                self.egui_ctx.wants_pointer_input();
             }
             // Handle the mouse button being released
-            Event::MouseButtonUp { mouse_btn, .. } => {
-                let btn = match mouse_btn {
-                    MouseButton::Left => Some(egui::PointerButton::Primary),
-                    MouseButton::Middle => Some(egui::PointerButton::Middle),
-                    MouseButton::Right => Some(egui::PointerButton::Secondary),
-                    _ => None,
-                };
+            Event::MouseButtonUp {
+                mouse_btn, x, y, ..
+            } => {
+                self.move_pointer_to(*x, *y);
+                let btn = mouse_btn.to_egui_pointer_button();
                 if let Some(btn) = btn {
+                    self.pressed_buttons.retain(|pressed| *pressed != btn);
                     self.raw_input.events.push(egui::Event::PointerButton {
                         pos: self.pointer_pos,
                         button: btn,
@@ -97,147 +432,1060 @@ impl Platform {
                         modifiers: self.modifiers,
                     });
                 }
-                self.egui_ctx.wants_pointer_input();
+                // A drag that ended outside the window leaves the pointer outside
+                if self.pressed_buttons.is_empty() && !self.mouse_in_window {
+                    self.raw_input.events.push(egui::Event::PointerGone);
+                }
             }
             // Handle mouse motion
-            Event::MouseMotion { x, y, .. } => {
-                // Update the pointer position
-                self.pointer_pos = egui::Pos2::new(*x as f32, *y as f32);
+            Event::MouseMotion {
+                x, y, xrel, yrel, ..
+            } => {
+                // A real mouse is in use again
+                self.touch_in_use = false;
+                // Report the raw movement, which keeps coming in relative mode
                 self.raw_input
                     .events
-                    .push(egui::Event::PointerMoved(self.pointer_pos));
-                self.egui_ctx.wants_pointer_input();
+                    .push(egui::Event::MouseMoved(egui::Vec2::new(
+                        *xrel as f32,
+                        *yrel as f32,
+                    )));
+                // Update the pointer position
+                self.move_pointer_to(*x, *y);
             }
             // Handle the mouse scrolling
-            Event::MouseWheel { x, y, .. } => {
-                // Calculate the delta
-                let delta = egui::Vec2::new(*x as f32 * 8.0, *y as f32 * 8.0);
-                // Check the mod state
-                use sdl2::keyboard::Mod;
-                let left_ctrl = sdl.keyboard().mod_state() & Mod::LCTRLMOD == Mod::LCTRLMOD;
-                let right_ctrl = sdl.keyboard().mod_state() & Mod::RCTRLMOD == Mod::RCTRLMOD;
-
-                // Push the egui event
-                if left_ctrl || right_ctrl {
-                    self.raw_input
-                        .events
-                        .push(egui::Event::Zoom((delta.y / 125.0).exp()));
-                }
-                self.egui_ctx.wants_pointer_input();
+            Event::MouseWheel {
+                x,
+                y,
+                precise_x,
+                precise_y,
+                ..
+            } => {
+                // Prefer the precise delta, older SDL versions only report the integer one
+                let delta = if *precise_x != 0.0 || *precise_y != 0.0 {
+                    egui::Vec2::new(*precise_x, *precise_y)
+                } else {
+                    egui::Vec2::new(*x as f32, *y as f32)
+                };
+                // Egui turns the wheel into zooming or horizontal scrolling through its input options
+                let (unit, delta) = match self.scroll_speed {
+                    Some(points_per_line) => (egui::MouseWheelUnit::Point, delta * points_per_line),
+                    None => (egui::MouseWheelUnit::Line, delta),
+                };
+                self.raw_input.events.push(egui::Event::MouseWheel {
+                    unit,
+                    delta,
+                    modifiers: self.modifiers,
+                });
             }
 
             // Handle a key being pressed
             Event::KeyDown {
-                keycode, keymod, ..
+                keycode,
+                scancode,
+                repeat,
+                ..
             } => {
-                // Make sure there is a keycode
-                if let Some(keycode) = keycode {
-                    // Convert the keycode to an egui key
-                    if let Some(key) = keycode.to_egui_key() {
-                        // Check the modifiers
-                        use sdl2::keyboard::Mod;
-                        let alt = (*keymod & Mod::LALTMOD == Mod::LALTMOD)
-                            || (*keymod & Mod::RALTMOD == Mod::RALTMOD);
-                        let ctrl = (*keymod & Mod::LCTRLMOD == Mod::LCTRLMOD)
-                            || (*keymod & Mod::RCTRLMOD == Mod::RCTRLMOD);
-                        let shift = (*keymod & Mod::LSHIFTMOD == Mod::LSHIFTMOD)
-                            || (*keymod & Mod::RSHIFTMOD == Mod::RSHIFTMOD);
-                        let mac_cmd = *keymod & Mod::LGUIMOD == Mod::LGUIMOD;
-                        let command = (*keymod & Mod::LCTRLMOD == Mod::LCTRLMOD)
-                            || (*keymod & Mod::LGUIMOD == Mod::LGUIMOD);
-
-                        // Handle Cut Copy and paste
-                        match key {
-                            egui::Key::C => self.raw_input.events.push(egui::Event::Copy),
-                            egui::Key::X => self.raw_input.events.push(egui::Event::Cut),
-                            egui::Key::V => {
-                                let clipboard = video.clipboard();
-                                if clipboard.has_clipboard_text() {
-                                    self.raw_input.events.push(egui::Event::Text(
-                                        clipboard.clipboard_text().unwrap(),
-                                    ));
-                                }
-                            }
-                            _ => {}
+                // The physical key does not depend on the keyboard layout
+                let physical_key = scancode.and_then(|scancode| scancode.to_egui_physical_key());
+                // Convert the keycode to an egui key, falling back to the physical key
+                let key = keycode
+                    .and_then(|keycode| keycode.to_egui_key())
+                    .or(physical_key);
+                if let Some(key) = key {
+                    // Remember the key so its release matches even if the layout changes
+                    if let Some(scancode) = scancode {
+                        self.pressed_keys.insert(*scancode, key);
+                    }
+                    // Handle Cut Copy and paste
+                    match self.clipboard_shortcuts.action(self.modifiers, key) {
+                        Some(ClipboardAction::Copy) => {
+                            self.raw_input.events.push(egui::Event::Copy)
                         }
-
-                        // Update the modifiers
-                        self.modifiers = Modifiers {
-                            alt,
-                            ctrl,
-                            shift,
-                            mac_cmd,
-                            command,
-                        };
-                        self.raw_input.modifiers = self.modifiers;
-                        // Push the event
-                        self.raw_input.events.push(egui::Event::Key {
-                            key,
-                            physical_key: Some(key),
-                            pressed: true,
-                            repeat: false,
-                            modifiers: self.modifiers,
-                        });
+                        Some(ClipboardAction::Cut) => self.raw_input.events.push(egui::Event::Cut),
+                        Some(ClipboardAction::Paste) => match self.clipboard(video).text() {
+                            Ok(Some(text)) => self.raw_input.events.push(egui::Event::Paste(text)),
+                            Ok(None) => {}
+                            Err(e) => log::warn!("{}", e),
+                        },
+                        None => {}
                     }
+
+                    // Push the event
+                    self.raw_input.events.push(egui::Event::Key {
+                        key,
+                        physical_key,
+                        pressed: true,
+                        repeat: *repeat,
+                        modifiers: self.modifiers,
+                    });
                 }
-                self.egui_ctx.wants_keyboard_input();
             }
             // Handle a key being released
             Event::KeyUp {
-                keycode, keymod, ..
+                keycode, scancode, ..
             } => {
-                // Make sure there is a keycode
-                if let Some(keycode) = keycode {
-                    // Convert the keycode to an egui key
-                    if let Some(key) = keycode.to_egui_key() {
-                        // Check the modifiers
-                        use sdl2::keyboard::Mod;
-                        let alt = (*keymod & Mod::LALTMOD == Mod::LALTMOD)
-                            || (*keymod & Mod::RALTMOD == Mod::RALTMOD);
-                        let ctrl = (*keymod & Mod::LCTRLMOD == Mod::LCTRLMOD)
-                            || (*keymod & Mod::RCTRLMOD == Mod::RCTRLMOD);
-                        let shift = (*keymod & Mod::LSHIFTMOD == Mod::LSHIFTMOD)
-                            || (*keymod & Mod::RSHIFTMOD == Mod::RSHIFTMOD);
-                        let mac_cmd = *keymod & Mod::LGUIMOD == Mod::LGUIMOD;
-                        let command = (*keymod & Mod::LCTRLMOD == Mod::LCTRLMOD)
-                            || (*keymod & Mod::LGUIMOD == Mod::LGUIMOD);
-
-                        // Update the modifiers
-                        self.modifiers = Modifiers {
-                            alt,
-                            ctrl,
-                            shift,
-                            mac_cmd,
-                            command,
-                        };
-                        self.raw_input.modifiers = self.modifiers;
-                        // Push the event
-                        self.raw_input.events.push(egui::Event::Key {
-                            key,
-                            physical_key: Some(key),
-                            pressed: false,
-                            repeat: false,
-                            modifiers: self.modifiers,
-                        });
+                // The physical key does not depend on the keyboard layout
+                let physical_key = scancode.and_then(|scancode| scancode.to_egui_physical_key());
+                // Release the key that was pressed, falling back to the current layout
+                let key = scancode
+                    .and_then(|scancode| self.pressed_keys.remove(&scancode))
+                    .or_else(|| keycode.and_then(|keycode| keycode.to_egui_key()))
+                    .or(physical_key);
+                if let Some(key) = key {
+                    // Push the event
+                    self.raw_input.events.push(egui::Event::Key {
+                        key,
+                        physical_key,
+                        pressed: false,
+                        repeat: false,
+                        modifiers: self.modifiers,
+                    });
+                }
+            }
+            // Release keys whose meaning changed with the new keyboard layout
+            Event::Unknown { type_, .. } if *type_ == SDL_EventType::SDL_KEYMAPCHANGED as u32 => {
+                let modifiers = self.modifiers;
+                let events = &mut self.raw_input.events;
+                self.pressed_keys.retain(|scancode, key| {
+                    let physical_key = scancode.to_egui_physical_key();
+                    let new_key = Keycode::from_scancode(*scancode)
+                        .and_then(|keycode| keycode.to_egui_key())
+                        .or(physical_key);
+                    if new_key == Some(*key) {
+                        return true;
                     }
+                    events.push(egui::Event::Key {
+                        key: *key,
+                        physical_key,
+                        pressed: false,
+                        repeat: false,
+                        modifiers,
+                    });
+                    false
+                });
+            }
+            // Handle touches
+            Event::FingerDown {
+                timestamp,
+                touch_id,
+                finger_id,
+                x,
+                y,
+                pressure,
+                ..
+            } => self.handle_touch(
+                egui::TouchPhase::Start,
+                (*touch_id, *finger_id),
+                Pos2::new(*x, *y),
+                *pressure,
+                *timestamp,
+            ),
+            Event::FingerMotion {
+                timestamp,
+                touch_id,
+                finger_id,
+                x,
+                y,
+                pressure,
+                ..
+            } => self.handle_touch(
+                egui::TouchPhase::Move,
+                (*touch_id, *finger_id),
+                Pos2::new(*x, *y),
+                *pressure,
+                *timestamp,
+            ),
+            Event::FingerUp {
+                timestamp,
+                touch_id,
+                finger_id,
+                x,
+                y,
+                pressure,
+                ..
+            } => self.handle_touch(
+                egui::TouchPhase::End,
+                (*touch_id, *finger_id),
+                Pos2::new(*x, *y),
+                *pressure,
+                *timestamp,
+            ),
+            // Pinching with multiple fingers zooms
+            Event::MultiGesture {
+                d_dist,
+                num_fingers,
+                ..
+            } if *num_fingers >= 2 => {
+                self.raw_input
+                    .events
+                    .push(egui::Event::Zoom((d_dist * PINCH_ZOOM_SPEED).exp()));
+            }
+
+            // Handle the text being composed by an input method.
+            // The extended `SDL_TEXTEDITING_EXT` events can't be read through sdl2's `Event`,
+            // so long compositions arrive truncated like sdl does by default.
+            Event::TextEditing { text, .. } => {
+                if !self.ime_composing && !text.is_empty() {
+                    self.ime_composing = true;
+                    self.raw_input
+                        .events
+                        .push(egui::Event::Ime(egui::ImeEvent::Enabled));
+                }
+                if self.ime_composing {
+                    self.raw_input
+                        .events
+                        .push(egui::Event::Ime(egui::ImeEvent::Preedit(text.clone())));
                 }
-                self.egui_ctx.wants_keyboard_input();
             }
-            // Handle text input
+            // Handle text input, which ends a composition
             Event::TextInput { text, .. } => {
-                self.raw_input.events.push(egui::Event::Text(text.clone()));
-                self.egui_ctx.wants_keyboard_input();
+                if self.ime_composing {
+                    self.ime_composing = false;
+                    self.raw_input
+                        .events
+                        .push(egui::Event::Ime(egui::ImeEvent::Commit(text.clone())));
+                } else {
+                    self.raw_input.events.push(egui::Event::Text(text.clone()));
+                }
+            }
+
+            // Handle files and text dropped on the window
+            Event::DropBegin { .. } => {
+                self.dropping = true;
+            }
+            Event::DropFile { filename, .. } => {
+                let path = PathBuf::from(filename);
+                // Sdl2 only reports files once they are dropped, so egui never sees them hovered
+                if self.dropping {
+                    self.dropped_files.push(dropped_file(path));
+                } else {
+                    // Older sdl versions drop files without beginning a drop
+                    self.raw_input.dropped_files.push(dropped_file(path));
+                }
+            }
+            Event::DropText { filename, .. } => {
+                self.raw_input
+                    .events
+                    .push(egui::Event::Text(filename.clone()));
+            }
+            Event::DropComplete { .. } => {
+                self.dropping = false;
+                self.raw_input.dropped_files.append(&mut self.dropped_files);
             }
 
             _ => {}
         }
+        self.update_mouse_capture(sdl);
+        response
+    }
+
+    /// Handle every pending event, yielding only the ones egui did not consume
+    pub fn poll_unconsumed_events<'a>(
+        &'a mut self,
+        event_pump: &'a mut sdl2::EventPump,
+        sdl: &'a sdl2::Sdl,
+        video: &'a sdl2::VideoSubsystem,
+    ) -> impl Iterator<Item = Event> + 'a {
+        event_pump
+            .poll_iter()
+            .filter(move |event| !self.handle_event(event, sdl, video).consumed)
+    }
+
+    /// Decide from the state of the previous pass whether egui consumes an event
+    fn event_response(&self, event: &Event) -> EventResponse {
+        match event {
+            // The pointer is over an egui area or dragging something in egui
+            Event::MouseMotion { .. }
+            | Event::MouseButtonDown { .. }
+            | Event::MouseButtonUp { .. }
+            | Event::MouseWheel { .. }
+            | Event::FingerDown { .. }
+            | Event::FingerMotion { .. }
+            | Event::FingerUp { .. }
+            | Event::MultiGesture { .. } => EventResponse {
+                consumed: self.egui_ctx.wants_pointer_input() || self.egui_ctx.is_using_pointer(),
+                repaint: true,
+            },
+            // A text field has keyboard focus
+            Event::KeyDown { .. }
+            | Event::KeyUp { .. }
+            | Event::TextEditing { .. }
+            | Event::TextInput { .. } => EventResponse {
+                consumed: self.egui_ctx.wants_keyboard_input(),
+                repaint: true,
+            },
+            // Dropped text goes to the focused text field
+            Event::DropText { .. } => EventResponse {
+                consumed: self.egui_ctx.wants_keyboard_input(),
+                repaint: true,
+            },
+            // Everything else is shared with the application
+            Event::Window { .. }
+            | Event::Quit { .. }
+            | Event::DropBegin { .. }
+            | Event::DropFile { .. }
+            | Event::DropComplete { .. } => EventResponse {
+                consumed: false,
+                repaint: true,
+            },
+            Event::Unknown { type_, .. } if *type_ == SDL_EventType::SDL_KEYMAPCHANGED as u32 => {
+                EventResponse {
+                    consumed: false,
+                    repaint: true,
+                }
+            }
+            _ => EventResponse::default(),
+        }
+    }
+
+    /// Move the pointer to a window position, if it is not there already
+    fn move_pointer_to(&mut self, x: i32, y: i32) {
+        // The pointer stays at its last position in relative mode, so widgets don't jump
+        if self.relative_mouse {
+            return;
+        }
+        let pos = Pos2::new(x as f32, y as f32) * self.points_per_window_unit();
+        if self.pointer_pos != pos {
+            self.pointer_pos = pos;
+            self.raw_input.events.push(egui::Event::PointerMoved(pos));
+        }
+    }
+
+    /// Send a touch to egui, the first finger down also drives the pointer
+    fn handle_touch(
+        &mut self,
+        phase: egui::TouchPhase,
+        (touch_id, finger_id): (i64, i64),
+        normalized: Pos2,
+        pressure: f32,
+        timestamp: u32,
+    ) {
+        // Sdl reports positions normalized to the window
+        let size = self.window_size * self.points_per_window_unit();
+        let pos = Pos2::new(normalized.x * size.x, normalized.y * size.y);
+        self.raw_input.events.push(egui::Event::Touch {
+            device_id: egui::TouchDeviceId(touch_id as u64),
+            id: egui::TouchId(finger_id as u64),
+            phase,
+            pos,
+            force: Some(pressure),
+        });
+
+        // Only touch screens drive the pointer, trackpad positions are not window positions
+        let device_type = unsafe { sdl2::sys::SDL_GetTouchDeviceType(touch_id) };
+        if device_type != sdl2::sys::SDL_TouchDeviceType::SDL_TOUCH_DEVICE_DIRECT {
+            return;
+        }
+
+        // Emulate the pointer with the first finger, like sdl does for the mouse
+        if phase == egui::TouchPhase::Start && self.pointer_touch.is_none() {
+            self.pointer_touch = Some((touch_id, finger_id));
+        }
+        if self.pointer_touch != Some((touch_id, finger_id)) {
+            return;
+        }
+        self.pointer_pos = pos;
+
+        // The touch mode turns the finger into gestures instead
+        if let Some(touch_mode) = &self.touch_mode {
+            self.touch_in_use = true;
+            let events = &mut self.raw_input.events;
+            match phase {
+                egui::TouchPhase::Start => self.touch_gestures.start(
+                    &self.egui_ctx,
+                    touch_mode,
+                    pos,
+                    timestamp,
+                    Instant::now(),
+                    events,
+                ),
+                egui::TouchPhase::Move => {
+                    self.touch_gestures
+                        .move_to(touch_mode, pos, timestamp, self.modifiers, events)
+                }
+                egui::TouchPhase::End => self.touch_gestures.end(
+                    &self.egui_ctx,
+                    touch_mode,
+                    Instant::now(),
+                    self.modifiers,
+                    events,
+                ),
+                egui::TouchPhase::Cancel => self.touch_gestures.cancel(events),
+            }
+            if matches!(phase, egui::TouchPhase::End | egui::TouchPhase::Cancel) {
+                self.pointer_touch = None;
+            }
+            return;
+        }
+
+        self.raw_input.events.push(egui::Event::PointerMoved(pos));
+        match phase {
+            egui::TouchPhase::Start | egui::TouchPhase::End | egui::TouchPhase::Cancel => {
+                let pressed = phase == egui::TouchPhase::Start;
+                self.raw_input.events.push(egui::Event::PointerButton {
+                    pos,
+                    button: egui::PointerButton::Primary,
+                    pressed,
+                    modifiers: self.modifiers,
+                });
+                if !pressed {
+                    self.pointer_touch = None;
+                    self.raw_input.events.push(egui::Event::PointerGone);
+                }
+            }
+            egui::TouchPhase::Move => {}
+        }
+    }
+
+    /// Send a release for every key that is still held
+    fn release_held_keys(&mut self) {
+        for (scancode, key) in self.pressed_keys.drain() {
+            self.raw_input.events.push(egui::Event::Key {
+                key,
+                physical_key: scancode.to_egui_physical_key(),
+                pressed: false,
+                repeat: false,
+                modifiers: self.modifiers,
+            });
+        }
+    }
+
+    /// Capture the mouse while a button is held, so drags keep going outside the window
+    fn update_mouse_capture(&mut self, sdl: &sdl2::Sdl) {
+        let capture = !self.pressed_buttons.is_empty();
+        if self.mouse_captured != capture {
+            self.mouse_captured = capture;
+            sdl.mouse().capture(capture);
+        }
+    }
+
+    /// Send a release for every pointer button that is still held
+    fn release_held_buttons(&mut self) {
+        for button in self.pressed_buttons.drain(..) {
+            self.raw_input.events.push(egui::Event::PointerButton {
+                pos: self.pointer_pos,
+                button,
+                pressed: false,
+                modifiers: self.modifiers,
+            });
+        }
+    }
+
+    /// Let go of the finger driving the pointer
+    fn release_pointer_touch(&mut self) {
+        // Outside touch mode the finger pressed the primary button
+        if self.pointer_touch.take().is_some() && self.touch_mode.is_none() {
+            self.raw_input.events.push(egui::Event::PointerButton {
+                pos: self.pointer_pos,
+                button: egui::PointerButton::Primary,
+                pressed: false,
+                modifiers: self.modifiers,
+            });
+        }
+        self.touch_gestures.cancel(&mut self.raw_input.events);
     }
 
     /// Set the pixels per point
+    ///
+    /// This sets egui's zoom factor relative to the native pixels per point, which
+    /// [`egui::Context::set_zoom_factor`] can also change directly.
     pub fn set_pixels_per_point(&mut self, pixels_per_point: f32) {
         self.context().set_pixels_per_point(pixels_per_point);
     }
 
+    /// Get the pixels per point of the display the window is on, before any zoom
+    pub fn native_pixels_per_point(&self) -> f32 {
+        self.native_pixels_per_point
+    }
+
+    /// Update the window size, display scale and viewport info from the window,
+    /// call it before [`Platform::context`]
+    ///
+    /// The scale follows the ratio of the drawable size to the window size,
+    /// only on windows without a high dpi drawable it follows the display dpi.
+    pub fn update_window(&mut self, window: &sdl2::video::Window) {
+        self.window_id = Some(window.id());
+        let (width, height) = window.size();
+        let (drawable_width, _) = window.drawable_size();
+        self.window_size = egui::Vec2::new(width as f32, height as f32);
+        if width > 0 && drawable_width > 0 {
+            let pixels_per_window_unit = drawable_width as f32 / width as f32;
+            if pixels_per_window_unit != self.pixels_per_window_unit {
+                self.pixels_per_window_unit = pixels_per_window_unit;
+                self.scale_outdated = true;
+            }
+        }
+        if self.scale_outdated {
+            self.scale_outdated = false;
+            self.update_native_pixels_per_point(window);
+        }
+        self.update_viewport_info(window);
+    }
+
+    /// Get the area of the display the window is on that is not taken by task bars and docks, in points
+    pub fn usable_display_rect(&self) -> Option<egui::Rect> {
+        self.usable_display_rect
+    }
+
+    /// Query the pixels per point of the display the window is on
+    #[cfg_attr(not(windows), allow(unused_variables))]
+    fn update_native_pixels_per_point(&mut self, window: &sdl2::video::Window) {
+        self.native_pixels_per_point = self.pixels_per_window_unit;
+        // The display dpi is physical on x11 and macos, only windows reports the scaling set by the user
+        #[cfg(windows)]
+        if self.pixels_per_window_unit <= 1.0 {
+            let dpi = window
+                .display_index()
+                .and_then(|display| window.subsystem().display_dpi(display));
+            match dpi {
+                // Round to quarter steps, the reported dpi is only approximate on some systems
+                Ok((ddpi, _, _)) => {
+                    self.native_pixels_per_point =
+                        ((ddpi / DEFAULT_DPI * 4.0).round() / 4.0).max(1.0);
+                }
+                Err(e) => log::warn!("Failed to get the display dpi: {}", e),
+            }
+        }
+    }
+
+    /// Describe the window and the display it is on to egui
+    fn update_viewport_info(&mut self, window: &sdl2::video::Window) {
+        let scale = self.points_per_window_unit();
+        let to_points = |rect: sdl2::rect::Rect| {
+            egui::Rect::from_min_size(
+                egui::Pos2::new(rect.x() as f32, rect.y() as f32) * scale,
+                egui::Vec2::new(rect.width() as f32, rect.height() as f32) * scale,
+            )
+        };
+
+        let (x, y) = window.position();
+        let (width, height) = window.size();
+        let inner = sdl2::rect::Rect::new(x, y, width, height);
+        // Not every window manager tells the size of the decorations
+        let outer = match window.border_size() {
+            Ok((top, left, bottom, right)) => sdl2::rect::Rect::new(
+                x - left as i32,
+                y - top as i32,
+                width + left as u32 + right as u32,
+                height + top as u32 + bottom as u32,
+            ),
+            Err(_) => inner,
+        };
+
+        let video = window.subsystem();
+        let display = window.display_index().ok();
+        let bounds = display.and_then(|display| video.display_bounds(display).ok());
+        self.usable_display_rect = display
+            .and_then(|display| video.display_usable_bounds(display).ok())
+            .map(to_points);
+
+        let info = self
+            .raw_input
+            .viewports
+            .entry(egui::ViewportId::ROOT)
+            .or_default();
+        info.title = Some(window.title().to_owned());
+        info.inner_rect = Some(to_points(inner));
+        info.outer_rect = Some(to_points(outer));
+        info.monitor_size = bounds.map(|bounds| to_points(bounds).size());
+        info.minimized = Some(window.is_minimized());
+        info.maximized = Some(window.is_maximized());
+        info.fullscreen = Some(window.fullscreen_state() != FullscreenType::Off);
+        info.focused = Some(window.has_input_focus());
+    }
+
+    /// The pixels per point egui draws with, including the zoom
+    fn pixels_per_point(&self) -> f32 {
+        self.native_pixels_per_point * self.egui_ctx.zoom_factor()
+    }
+
+    /// The points per window unit, to turn sdl coordinates into egui positions
+    fn points_per_window_unit(&self) -> f32 {
+        self.pixels_per_window_unit / self.pixels_per_point()
+    }
+
+    /// Set how the sdl modifier keys are turned into egui modifiers
+    pub fn set_modifier_policy(&mut self, modifier_policy: ModifierPolicy) {
+        self.modifier_policy = modifier_policy;
+    }
+
+    /// Set the keyboard shortcuts that trigger copy, cut and paste
+    pub fn set_clipboard_shortcuts(&mut self, clipboard_shortcuts: ClipboardShortcuts) {
+        self.clipboard_shortcuts = clipboard_shortcuts;
+    }
+
+    /// Set the clipboard used for copy, cut and paste instead of the sdl clipboard
+    pub fn set_clipboard_provider(&mut self, clipboard: impl ClipboardProvider + 'static) {
+        self.clipboard = Some(Box::new(clipboard));
+    }
+
+    /// Get the clipboard, falling back to the sdl clipboard
+    fn clipboard(&mut self, video: &sdl2::VideoSubsystem) -> &mut dyn ClipboardProvider {
+        self.clipboard
+            .get_or_insert_with(|| Box::new(SdlClipboard::new(video)))
+            .as_mut()
+    }
+
+    /// Set what happens to a copied image the clipboard could not take
+    pub fn set_copy_image_fallback(
+        &mut self,
+        fallback: impl FnMut(&egui::ColorImage) -> anyhow::Result<()> + 'static,
+    ) {
+        self.copy_image_fallback = Some(Box::new(fallback));
+    }
+
+    /// Put an image on the clipboard, handing it to the fallback on failure
+    fn copy_image(&mut self, video: &sdl2::VideoSubsystem, image: &egui::ColorImage) {
+        let Err(e) = self.clipboard(video).set_image(image) else {
+            return;
+        };
+        match &mut self.copy_image_fallback {
+            Some(fallback) => {
+                if let Err(e) = fallback(image) {
+                    log::warn!("Failed to handle the copied image: {}", e);
+                }
+            }
+            None => log::warn!("{}", e),
+        }
+    }
+
+    /// Set the handler that opens urls instead of [`open_url_with_sdl`], e.g. to apply an allow-list
+    pub fn set_url_handler(
+        &mut self,
+        url_handler: impl FnMut(&egui::OpenUrl) -> anyhow::Result<()> + 'static,
+    ) {
+        self.url_handler = Box::new(url_handler);
+    }
+
+    /// Set the touch-first interaction mode, `None` turns it off
+    ///
+    /// In touch mode a tap clicks, a long press is a secondary click and dragging scrolls with inertia.
+    pub fn set_touch_mode(&mut self, touch_mode: Option<TouchMode>) {
+        self.release_pointer_touch();
+        self.touch_mode = touch_mode;
+    }
+
+    /// Start text input while egui has a focused text field and place the input method there,
+    /// stopping it again once no text field is focused if the platform started it
+    fn update_text_input(
+        &mut self,
+        video: &sdl2::VideoSubsystem,
+        ime: Option<egui::output::IMEOutput>,
+    ) {
+        let text_input = video.text_input();
+        let Some(ime) = ime else {
+            // Text input the application started is left running
+            if self.started_text_input {
+                self.started_text_input = false;
+                text_input.stop();
+            }
+            if self.ime_composing {
+                self.ime_composing = false;
+                self.raw_input
+                    .events
+                    .push(egui::Event::Ime(egui::ImeEvent::Disabled));
+            }
+            self.ime_rect = None;
+            return;
+        };
+
+        if !text_input.is_active() {
+            self.started_text_input = true;
+            text_input.start();
+        }
+        // The candidate window goes below the cursor, in window units
+        let rect = ime.cursor_rect / self.points_per_window_unit();
+        if self.ime_rect != Some(rect) {
+            self.ime_rect = Some(rect);
+            text_input.set_rect(sdl2::rect::Rect::new(
+                rect.min.x.round() as i32,
+                rect.min.y.round() as i32,
+                rect.width().round().max(1.0) as u32,
+                rect.height().round().max(1.0) as u32,
+            ));
+        }
+    }
+
+    /// Set whether the platform shows the cursor icons egui asks for
+    pub fn set_cursor_management(&mut self, manage_cursor: bool) {
+        self.manage_cursor = manage_cursor;
+    }
+
+    /// Set the images shown for cursor icons sdl2 has no system cursor for
+    pub fn set_cursor_theme(&mut self, cursor_theme: CursorTheme) {
+        self.cursor_theme = cursor_theme;
+        self.image_cursors.clear();
+        self.cursor_icon = None;
+    }
+
+    /// Show the cursor icon egui asked for
+    fn update_cursor(&mut self, video: &sdl2::VideoSubsystem, cursor_icon: egui::CursorIcon) {
+        if !self.manage_cursor {
+            return;
+        }
+        let mouse = video.sdl().mouse();
+        // Egui asked for the cursor to be hidden
+        if !self.cursor_visible {
+            if self.cursor_icon.take().is_some() {
+                mouse.show_cursor(false);
+            }
+            return;
+        }
+        // Hide the cursor while touch is in use
+        if self.touch_in_use && self.touch_mode.is_some_and(|mode| mode.hide_cursor) {
+            if self.cursor_icon.take().is_some() {
+                mouse.show_cursor(false);
+            }
+            return;
+        }
+        // Rebuild the image cursors when the display scale changes, the ui zoom leaves them alone
+        let scale = if self.cursor_theme.scale_with_dpi {
+            self.native_pixels_per_point.round().max(1.0) as u32
+        } else {
+            1
+        };
+        if scale != self.image_cursor_scale {
+            self.image_cursors.clear();
+            self.image_cursor_scale = scale;
+            self.cursor_icon = None;
+        }
+        if self.cursor_icon == Some(cursor_icon) {
+            return;
+        }
+        self.cursor_icon = Some(cursor_icon);
+
+        // Prefer the image from the theme
+        if let Some(image) = self.cursor_theme.get(cursor_icon) {
+            if !self
+                .image_cursors
+                .iter()
+                .any(|(icon, _)| *icon == cursor_icon)
+            {
+                match image.to_sdl_cursor(scale) {
+                    Ok(cursor) => self.image_cursors.push((cursor_icon, cursor)),
+                    Err(e) => log::warn!("{}", e),
+                }
+            }
+            if let Some((_, cursor)) = self
+                .image_cursors
+                .iter()
+                .find(|(icon, _)| *icon == cursor_icon)
+            {
+                cursor.set();
+                mouse.show_cursor(true);
+                return;
+            }
+        }
+
+        // Fall back to the closest system cursor
+        let Some(system_cursor) = cursor_icon.to_sdl_system_cursor() else {
+            mouse.show_cursor(false);
+            return;
+        };
+        let cursor = match self.system_cursors.entry(system_cursor) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => match Cursor::from_system(system_cursor) {
+                Ok(cursor) => entry.insert(cursor),
+                Err(e) => {
+                    log::warn!("Failed to get cursor from systems cursor: {}", e);
+                    return;
+                }
+            },
+        };
+        cursor.set();
+        mouse.show_cursor(true);
+    }
+
+    /// Apply the viewport commands egui issued during the frame to the window
+    pub fn apply_viewport_commands(
+        &mut self,
+        window: &mut sdl2::video::Window,
+        full_output: &egui::FullOutput,
+    ) {
+        if let Some(viewport) = full_output.viewport_output.get(&egui::ViewportId::ROOT) {
+            for command in &viewport.commands {
+                if let Err(e) = self.apply_viewport_command(window, command) {
+                    log::warn!("{}", e);
+                }
+            }
+        }
+        self.update_hit_test(window);
+    }
+
+    /// Apply a single viewport command to the window
+    fn apply_viewport_command(
+        &mut self,
+        window: &mut sdl2::video::Window,
+        command: &egui::ViewportCommand,
+    ) -> anyhow::Result<()> {
+        // Egui gives sizes and positions in points, sdl takes window units
+        let scale = self.points_per_window_unit();
+        let to_window_units = |size: egui::Vec2| {
+            let size = size / scale;
+            (
+                size.x.round().max(1.0) as u32,
+                size.y.round().max(1.0) as u32,
+            )
+        };
+
+        match command {
+            egui::ViewportCommand::Title(title) => window
+                .set_title(title)
+                .map_err(|e| anyhow::anyhow!("Failed to set the window title: {}", e))?,
+            egui::ViewportCommand::InnerSize(size) => {
+                let (width, height) = to_window_units(*size);
+                window
+                    .set_size(width, height)
+                    .map_err(|e| anyhow::anyhow!("Failed to set the window size: {}", e))?;
+            }
+            egui::ViewportCommand::MinInnerSize(size) => {
+                let (width, height) = to_window_units(*size);
+                window
+                    .set_minimum_size(width, height)
+                    .map_err(|e| anyhow::anyhow!("Failed to set the minimum window size: {}", e))?;
+            }
+            egui::ViewportCommand::MaxInnerSize(size) => {
+                let (width, height) = to_window_units(*size);
+                window
+                    .set_maximum_size(width, height)
+                    .map_err(|e| anyhow::anyhow!("Failed to set the maximum window size: {}", e))?;
+            }
+            egui::ViewportCommand::OuterPosition(pos) => {
+                // Sdl positions the inside of the window, so step over the decorations
+                let (top, left, _, _) = window.border_size().unwrap_or_default();
+                let pos = *pos / scale;
+                window.set_position(
+                    WindowPos::Positioned(pos.x.round() as i32 + left as i32),
+                    WindowPos::Positioned(pos.y.round() as i32 + top as i32),
+                );
+            }
+            egui::ViewportCommand::Minimized(true) => window.minimize(),
+            egui::ViewportCommand::Maximized(true) => window.maximize(),
+            egui::ViewportCommand::Minimized(false) | egui::ViewportCommand::Maximized(false) => {
+                window.restore()
+            }
+            egui::ViewportCommand::Fullscreen(fullscreen) => window
+                .set_fullscreen(if *fullscreen {
+                    FullscreenType::Desktop
+                } else {
+                    FullscreenType::Off
+                })
+                .map_err(|e| anyhow::anyhow!("Failed to set fullscreen: {}", e))?,
+            egui::ViewportCommand::Decorations(decorations) => window.set_bordered(*decorations),
+            egui::ViewportCommand::Resizable(resizable) => window.set_resizable(*resizable),
+            egui::ViewportCommand::WindowLevel(level) => match level {
+                egui::WindowLevel::Normal => window.set_always_on_top(false),
+                egui::WindowLevel::AlwaysOnTop => window.set_always_on_top(true),
+                egui::WindowLevel::AlwaysOnBottom => {
+                    return Err(anyhow::anyhow!("Sdl2 can't keep a window below the others"))
+                }
+            },
+            egui::ViewportCommand::Icon(Some(icon)) => {
+                let mut rgba = icon.rgba.clone();
+                let surface = Surface::from_data(
+                    &mut rgba,
+                    icon.width,
+                    icon.height,
+                    icon.width * 4,
+                    PixelFormatEnum::RGBA32,
+                )
+                .map_err(|e| anyhow::anyhow!("Failed to create icon surface: {}", e))?;
+                window.set_icon(surface);
+            }
+            egui::ViewportCommand::Visible(true) => window.show(),
+            egui::ViewportCommand::Visible(false) => window.hide(),
+            egui::ViewportCommand::Focus => window.raise(),
+            egui::ViewportCommand::RequestUserAttention(attention) => window
+                .flash(match attention {
+                    egui::UserAttentionType::Critical => FlashOperation::UntilFocused,
+                    egui::UserAttentionType::Informational => FlashOperation::Briefly,
+                    egui::UserAttentionType::Reset => FlashOperation::Cancel,
+                })
+                .map_err(|e| anyhow::anyhow!("Failed to flash the window: {}", e))?,
+            egui::ViewportCommand::CursorGrab(grab) => self.set_cursor_grab(window, *grab),
+            egui::ViewportCommand::CursorVisible(visible) => {
+                self.cursor_visible = *visible;
+                self.cursor_icon = None;
+                window.subsystem().sdl().mouse().show_cursor(*visible);
+            }
+            egui::ViewportCommand::CursorPosition(pos) => {
+                let pos = *pos / scale;
+                window.subsystem().sdl().mouse().warp_mouse_in_window(
+                    window,
+                    pos.x.round() as i32,
+                    pos.y.round() as i32,
+                );
+            }
+            // The clipboard requests arrive with the next frame, like the shortcuts do
+            egui::ViewportCommand::RequestCopy => self.raw_input.events.push(egui::Event::Copy),
+            egui::ViewportCommand::RequestCut => self.raw_input.events.push(egui::Event::Cut),
+            egui::ViewportCommand::RequestPaste => {
+                if let Some(text) = self.clipboard(window.subsystem()).text()? {
+                    self.raw_input.events.push(egui::Event::Paste(text));
+                }
+            }
+            // The window manager moves the window through the hit test, which has already
+            // let this press through, so the dragged widget moves the window from its next press
+            egui::ViewportCommand::StartDrag if self.hit_test_border.is_some() => {
+                match self.egui_ctx.dragged_id() {
+                    Some(id) if !self.drag_widgets.contains(&id) => {
+                        log::debug!("Dragging the window from the next press on {:?}", id);
+                        self.drag_widgets.push(id);
+                    }
+                    Some(_) => {}
+                    None => log::debug!("Ignoring a window drag without a dragged widget"),
+                }
+            }
+            egui::ViewportCommand::BeginResize(direction) if self.hit_test_border.is_some() => {
+                log::debug!(
+                    "Ignoring the {:?} resize, the hit test resizes from the window edges",
+                    direction
+                );
+            }
+            // Closing is decided when the frame ends
+            egui::ViewportCommand::Close | egui::ViewportCommand::CancelClose => {}
+            // The input method follows the platform output instead
+            egui::ViewportCommand::IMERect(_)
+            | egui::ViewportCommand::IMEAllowed(_)
+            | egui::ViewportCommand::IMEPurpose(_) => {}
+            command => {
+                return Err(anyhow::anyhow!(
+                    "Unsupported viewport command: {:?}",
+                    command
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Let the window manager move and resize a borderless window, `None` turns it off
+    ///
+    /// The window is resized from its edges, `border_width` points wide, and moved by the areas
+    /// given to [`Platform::add_drag_area`] and the widgets that sent
+    /// [`egui::ViewportCommand::StartDrag`], except where other interactive widgets are on top.
+    ///
+    /// Sdl decides whether a press moves the window before egui sees it, so the first
+    /// [`egui::ViewportCommand::StartDrag`] of a widget cannot move the window, only its later
+    /// presses do. Those presses go to the window manager, so the widget no longer sees them.
+    pub fn set_hit_test(&mut self, border_width: Option<f32>) {
+        self.hit_test_border = border_width;
+    }
+
+    /// Move the window when this area is dragged during the current frame, in points
+    pub fn add_drag_area(&mut self, rect: egui::Rect) {
+        self.drag_areas.push(rect);
+    }
+
+    /// Install or remove the hit test and tell it the areas of this frame
+    fn update_hit_test(&mut self, window: &sdl2::video::Window) {
+        let drag_areas = std::mem::take(&mut self.drag_areas);
+        if self.hit_test_data.is_some() != self.hit_test_border.is_some() {
+            let areas = self.hit_test_border.map(|_| &self.hit_test_areas);
+            if let Err(e) = set_window_hit_test(window, areas, &mut self.hit_test_data) {
+                log::warn!("{}", e);
+                // Don't try again every frame
+                self.hit_test_border = None;
+            }
+        }
+        let Some(border_width) = self.hit_test_border else {
+            self.drag_widgets.clear();
+            return;
+        };
+
+        // Forget the widgets that are gone, and follow the ones that moved
+        let egui_ctx = &self.egui_ctx;
+        self.drag_widgets
+            .retain(|id| egui_ctx.read_response(*id).is_some());
+        let drag_areas: Vec<egui::Rect> = drag_areas
+            .into_iter()
+            .chain(
+                self.drag_widgets
+                    .iter()
+                    .filter_map(|id| egui_ctx.read_response(*id))
+                    .map(|response| response.rect),
+            )
+            .collect();
+        // The interactive widgets of the last pass keep their presses, except the containers
+        // that hold a whole drag area, like a movable window around its title bar
+        let interactive_areas: Vec<egui::Rect> = egui_ctx.viewport(|viewport| {
+            viewport
+                .prev_pass
+                .widgets
+                .layers()
+                .flat_map(|(_, widgets)| widgets)
+                .filter(|widget| {
+                    widget.enabled
+                        && widget.sense.interactive()
+                        && !self.drag_widgets.contains(&widget.id)
+                })
+                .map(|widget| widget.interact_rect)
+                .filter(|rect| {
+                    drag_areas
+                        .iter()
+                        .any(|area| area.intersects(*rect) && !rect.contains_rect(*area))
+                })
+                .collect()
+        });
+
+        let scale = self.points_per_window_unit();
+        let to_window_units =
+            |rect: egui::Rect| egui::Rect::from_min_max(rect.min / scale, rect.max / scale);
+        let drag_areas = drag_areas.into_iter().map(to_window_units).collect();
+        let interactive_areas = interactive_areas.into_iter().map(to_window_units).collect();
+
+        let flags = window.window_flags();
+        let resizable = flags & sdl2::sys::SDL_WindowFlags::SDL_WINDOW_RESIZABLE as u32 != 0
+            && !window.is_maximized()
+            && window.fullscreen_state() == FullscreenType::Off;
+        let (width, height) = window.size();
+        if let Ok(mut areas) = self.hit_test_areas.lock() {
+            *areas = HitTestAreas {
+                drag_areas,
+                interactive_areas,
+                border_width: border_width / scale,
+                window_size: egui::Vec2::new(width as f32, height as f32),
+                resizable,
+            };
+        }
+    }
+
+    /// Get the resize cursor for the window edge under the pointer
+    fn hit_test_cursor_icon(&self) -> Option<egui::CursorIcon> {
+        if self.hit_test_data.is_none() || !self.mouse_in_window {
+            return None;
+        }
+        let pos = self.pointer_pos / self.points_per_window_unit();
+        let areas = self.hit_test_areas.lock().ok()?;
+        areas.resize_direction(pos).map(resize_cursor_icon)
+    }
+
+    /// Confine the cursor to the window or lock it in place with relative mouse mode
+    fn set_cursor_grab(&mut self, window: &mut sdl2::video::Window, grab: egui::CursorGrab) {
+        if self.cursor_grab == grab {
+            return;
+        }
+        self.cursor_grab = grab;
+        let mouse = window.subsystem().sdl().mouse();
+        let relative = grab == egui::CursorGrab::Locked;
+        mouse.set_relative_mouse_mode(relative);
+        // Sdl may refuse relative mode, in which case the pointer keeps moving
+        self.relative_mouse = relative && mouse.relative_mouse_mode();
+        window.set_mouse_grab(grab != egui::CursorGrab::None);
+    }
+
+    /// Set how many points a single mouse wheel line scrolls.
+    ///
+    /// With `None` (the default) the wheel is reported in lines and egui picks the line height.
+    pub fn set_scroll_speed(&mut self, points_per_line: Option<f32>) {
+        self.scroll_speed = points_per_line;
+    }
+
+    /// Whether the window can be seen, rendering can be skipped while it is minimized or hidden
+    pub fn is_visible(&self) -> bool {
+        !self.minimized && !self.hidden
+    }
+
     /// Update the time
     pub fn update_time(&mut self, duration: f64) {
         self.raw_input.time = Some(duration);
@@ -245,6 +1493,34 @@ impl Platform {
 
     /// Return the processed context
     pub fn context(&mut self) -> egui::Context {
+        // Fire long presses, settle drags and keep scrolling after a swipe
+        if let Some(touch_mode) = self
+            .touch_mode
+            .as_ref()
+            .filter(|_| self.touch_gestures.is_active())
+        {
+            self.touch_gestures.update(
+                &self.egui_ctx,
+                touch_mode,
+                Instant::now(),
+                self.modifiers,
+                &mut self.raw_input.events,
+            );
+        }
+        // Give egui the screen in points
+        let size_in_pixels = self.window_size * self.pixels_per_window_unit;
+        self.raw_input.screen_rect = Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            size_in_pixels / self.pixels_per_point(),
+        ));
+        self.raw_input
+            .viewports
+            .entry(egui::ViewportId::ROOT)
+            .or_default()
+            .native_pixels_per_point = Some(self.native_pixels_per_point);
+        // Egui sees the close request during this pass
+        self.close_requested_in_pass = std::mem::take(&mut self.close_requested);
+        self.primary_paste_in_pass = self.primary_paste.take();
         // Begin the frame
         self.egui_ctx.begin_pass(self.raw_input.take());
         // Return the ctx
@@ -262,51 +1538,140 @@ impl Platform {
         for cmd in &output.platform_output.commands {
             match cmd {
                 egui::OutputCommand::CopyText(text) => {
-                    video
-                        .clipboard()
-                        .set_clipboard_text(&text)
-                        .map_err(|e| anyhow::anyhow!("Failed to assign text to clipboard: {}", e))?;
+                    if let Err(e) = self.clipboard(video).set_text(text) {
+                        log::warn!("{}", e);
+                    }
                 }
-                egui::OutputCommand::CopyImage(_) | egui::OutputCommand::OpenUrl(_) => {
-                    // TODO: Handle CopyImage and OpenUrl commands
+                egui::OutputCommand::CopyImage(image) => self.copy_image(video, image),
+                egui::OutputCommand::OpenUrl(open_url) => {
+                    if let Err(e) = (self.url_handler)(open_url) {
+                        log::warn!("Failed to open url {}: {}", open_url.url, e);
+                    }
                 }
             }
         }
 
-        if let Some(cursor) = &mut self.cursor {
-            // Update the cursor icon
-            let new_cursor = match output.platform_output.cursor_icon {
-                egui::CursorIcon::Crosshair => SystemCursor::Crosshair,
-                egui::CursorIcon::Default => SystemCursor::Arrow,
-                egui::CursorIcon::Grab => SystemCursor::Hand,
-                egui::CursorIcon::Grabbing => SystemCursor::SizeAll,
-                egui::CursorIcon::Move => SystemCursor::SizeAll,
-                egui::CursorIcon::PointingHand => SystemCursor::Hand,
-                egui::CursorIcon::ResizeHorizontal => SystemCursor::SizeWE,
-                egui::CursorIcon::ResizeNeSw => SystemCursor::SizeNESW,
-                egui::CursorIcon::ResizeNwSe => SystemCursor::SizeNWSE,
-                egui::CursorIcon::ResizeVertical => SystemCursor::SizeNS,
-                egui::CursorIcon::Text => SystemCursor::IBeam,
-                egui::CursorIcon::NotAllowed | egui::CursorIcon::NoDrop => SystemCursor::No,
-                egui::CursorIcon::Wait => SystemCursor::Wait,
-                _ => SystemCursor::Arrow,
-            };
-
-            if self.system_cursor != new_cursor {
-                self.system_cursor = new_cursor;
-                *cursor = Cursor::from_system(new_cursor).map_err(|e| {
-                    anyhow::anyhow!("Failed to get cursor from systems cursor: {}", e)
-                })?;
-                cursor.set();
+        // Fill the primary selection with the text selected in a text edit
+        for event in &output.platform_output.events {
+            if let egui::output::OutputEvent::TextSelectionChanged(info) = event {
+                if let Some(text) = selected_text(info) {
+                    if let Err(e) = self.clipboard(video).set_primary_selection(&text) {
+                        log::warn!("{}", e);
+                    }
+                }
             }
         }
 
+        // Paste the primary selection into the text edit under a middle click
+        self.paste_primary_selection();
+        // Close unless egui cancelled the request
+        self.update_should_close(&output);
+        // Update the text input
+        self.update_text_input(video, output.platform_output.ime);
+        // Update the cursor icon, the edges of a borderless window show where it is resized
+        let cursor_icon = self
+            .hit_test_cursor_icon()
+            .unwrap_or(output.platform_output.cursor_icon);
+        self.update_cursor(video, cursor_icon);
+
         Ok(output)
     }
 
+    /// Paste a middle clicked primary selection, if egui focused a text edit under the click.
+    ///
+    /// Egui pastes into whatever has focus, so clicks elsewhere are left to the application.
+    fn paste_primary_selection(&mut self) {
+        let Some((text, pos)) = self.primary_paste_in_pass.take() else {
+            return;
+        };
+        let clicked_text_edit = self.egui_ctx.wants_keyboard_input()
+            && self
+                .egui_ctx
+                .memory(|memory| memory.focused())
+                .and_then(|id| self.egui_ctx.read_response(id))
+                .is_some_and(|response| response.rect.contains(pos));
+        if clicked_text_edit {
+            self.raw_input.events.push(egui::Event::Paste(text));
+        }
+    }
+
+    /// Whether the application should close, because closing was requested and not cancelled
+    pub fn should_close(&self) -> bool {
+        self.should_close
+    }
+
+    /// Set the native dialog that confirms closing when egui did not cancel it, `None` closes right away
+    pub fn set_close_confirmation(&mut self, close_confirmation: Option<CloseConfirmation>) {
+        self.close_confirmation = close_confirmation;
+    }
+
+    /// Tell egui closing was requested, it can cancel during the next pass
+    fn request_close(&mut self) {
+        self.close_requested = true;
+        self.raw_input
+            .viewports
+            .entry(egui::ViewportId::ROOT)
+            .or_default()
+            .events
+            .push(egui::ViewportEvent::Close);
+    }
+
+    /// Decide from the viewport commands of the pass whether the application closes
+    fn update_should_close(&mut self, output: &egui::FullOutput) {
+        let commands = output
+            .viewport_output
+            .get(&egui::ViewportId::ROOT)
+            .map(|viewport| viewport.commands.as_slice())
+            .unwrap_or_default();
+        // Egui asked to close by itself
+        if commands.contains(&egui::ViewportCommand::Close) {
+            self.should_close = true;
+            return;
+        }
+        if !self.close_requested_in_pass || commands.contains(&egui::ViewportCommand::CancelClose) {
+            return;
+        }
+        self.close_requested_in_pass = false;
+        self.should_close = match &self.close_confirmation {
+            Some(confirmation) => confirm_close(confirmation),
+            None => true,
+        };
+    }
+
     /// Tessellate the egui frame
     pub fn tessellate(&self, full_output: &egui::FullOutput) -> Vec<egui::ClippedPrimitive> {
         self.egui_ctx
             .tessellate(full_output.shapes.clone(), self.egui_ctx.pixels_per_point())
     }
 }
+
+/// Ask with a native dialog whether to close, closing when the dialog can't be shown
+fn confirm_close(confirmation: &CloseConfirmation) -> bool {
+    let buttons = [
+        ButtonData {
+            flags: MessageBoxButtonFlag::RETURNKEY_DEFAULT,
+            button_id: 1,
+            text: &confirmation.confirm,
+        },
+        ButtonData {
+            flags: MessageBoxButtonFlag::ESCAPEKEY_DEFAULT,
+            button_id: 0,
+            text: &confirmation.cancel,
+        },
+    ];
+    match show_message_box(
+        MessageBoxFlag::WARNING,
+        &buttons,
+        &confirmation.title,
+        &confirmation.message,
+        None,
+        None,
+    ) {
+        Ok(ClickedButton::CustomButton(button)) => button.button_id == 1,
+        Ok(ClickedButton::CloseButton) => false,
+        Err(e) => {
+            log::warn!("Failed to show the close confirmation: {}", e);
+            true
+        }
+    }
+}
@@ -0,0 +1,449 @@
+use std::time::{Duration, Instant};
+
+use egui::{Event, Modifiers, MouseWheelUnit, PointerButton, Pos2, Vec2};
+
+/// The settings of the touch-first interaction mode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchMode {
+    /// How long a finger has to rest before it becomes a secondary click
+    pub long_press_duration: Duration,
+    /// How far a finger may move, in points, before it drags or scrolls instead of clicking
+    pub drag_threshold: f32,
+    /// The part of the scroll velocity that is left after a second of inertia
+    pub inertia_friction: f32,
+    /// Whether the cursor is hidden while touch is in use
+    pub hide_cursor: bool,
+}
+
+impl Default for TouchMode {
+    fn default() -> Self {
+        Self {
+            long_press_duration: Duration::from_millis(500),
+            drag_threshold: 8.0,
+            inertia_friction: 0.05,
+            hide_cursor: true,
+        }
+    }
+}
+
+/// Inertia stops once the scroll velocity drops below this, in points per second
+const MIN_INERTIA_VELOCITY: f32 = 20.0;
+
+/// What a finger resting on or moving over the screen does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PressState {
+    /// The finger has not moved far enough to be more than a tap or a long press
+    Resting,
+    /// The finger moved and the press was sent, egui has yet to show whether it drags something
+    Deciding {
+        /// Whether a pass has seen the press
+        seen: bool,
+    },
+    /// Egui is dragging something with the finger
+    Dragging,
+    /// Nothing was dragged, so the finger scrolls
+    Scrolling,
+}
+
+/// A finger resting on or moving over the screen
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    start: Instant,
+    start_pos: Pos2,
+    last_pos: Pos2,
+    last_timestamp: u32,
+    state: PressState,
+}
+
+/// Turns the finger driving the pointer into clicks, secondary clicks, drags and scrolling
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TouchGestures {
+    press: Option<Press>,
+    velocity: Vec2,
+    last_update: Option<Instant>,
+    inertia_pos: Option<Pos2>,
+}
+
+impl TouchGestures {
+    /// Whether a finger is down or the content is still scrolling from one
+    pub fn is_active(&self) -> bool {
+        self.press.is_some() || self.inertia_pos.is_some()
+    }
+
+    /// A finger touched the screen
+    pub fn start(
+        &mut self,
+        ctx: &egui::Context,
+        mode: &TouchMode,
+        pos: Pos2,
+        timestamp: u32,
+        now: Instant,
+        events: &mut Vec<Event>,
+    ) {
+        self.stop_inertia(events);
+        self.press = Some(Press {
+            start: now,
+            start_pos: pos,
+            last_pos: pos,
+            last_timestamp: timestamp,
+            state: PressState::Resting,
+        });
+        self.velocity = Vec2::ZERO;
+        events.push(Event::PointerMoved(pos));
+        // Run a pass once the finger rested long enough, even if nothing else happens
+        ctx.request_repaint_after(mode.long_press_duration);
+    }
+
+    /// The finger moved, pressing once it moved far enough so egui can drag what is under it
+    pub fn move_to(
+        &mut self,
+        mode: &TouchMode,
+        pos: Pos2,
+        timestamp: u32,
+        modifiers: Modifiers,
+        events: &mut Vec<Event>,
+    ) {
+        let Some(press) = &mut self.press else {
+            return;
+        };
+        match press.state {
+            PressState::Resting => {
+                if (pos - press.start_pos).length() <= mode.drag_threshold {
+                    return;
+                }
+                press.state = PressState::Deciding { seen: false };
+                events.push(Event::PointerButton {
+                    pos: press.start_pos,
+                    button: PointerButton::Primary,
+                    pressed: true,
+                    modifiers,
+                });
+                events.push(Event::PointerMoved(pos));
+            }
+            PressState::Deciding { .. } | PressState::Dragging => {
+                events.push(Event::PointerMoved(pos));
+            }
+            PressState::Scrolling => {
+                let delta = pos - press.last_pos;
+                let dt = timestamp.wrapping_sub(press.last_timestamp) as f32 / 1000.0;
+                // Events can arrive in bursts, only sample the velocity over a measurable time
+                if dt > 0.0 {
+                    self.velocity = self.velocity * 0.2 + delta / dt * 0.8;
+                    press.last_timestamp = timestamp;
+                }
+                events.push(Event::MouseWheel {
+                    unit: MouseWheelUnit::Point,
+                    delta,
+                    modifiers,
+                });
+            }
+        }
+        press.last_pos = pos;
+    }
+
+    /// The finger was lifted, a tap becomes a click, a drag is released and scrolling keeps going
+    pub fn end(
+        &mut self,
+        ctx: &egui::Context,
+        mode: &TouchMode,
+        now: Instant,
+        modifiers: Modifiers,
+        events: &mut Vec<Event>,
+    ) {
+        let Some(press) = self.press.take() else {
+            return;
+        };
+        match press.state {
+            PressState::Resting => {
+                // The long press may not have been noticed if no pass ran in the meantime
+                let button = if now.duration_since(press.start) >= mode.long_press_duration {
+                    PointerButton::Secondary
+                } else {
+                    PointerButton::Primary
+                };
+                click(press.start_pos, button, modifiers, events);
+            }
+            PressState::Deciding { .. } | PressState::Dragging => {
+                release(press.last_pos, modifiers, events);
+            }
+            PressState::Scrolling => {
+                if self.velocity.length() >= MIN_INERTIA_VELOCITY {
+                    self.inertia_pos = Some(press.last_pos);
+                    self.last_update = Some(now);
+                    ctx.request_repaint();
+                    return;
+                }
+            }
+        }
+        events.push(Event::PointerGone);
+    }
+
+    /// The finger was lost without being lifted
+    pub fn cancel(&mut self, events: &mut Vec<Event>) {
+        if let Some(press) = self.press.take() {
+            if matches!(
+                press.state,
+                PressState::Deciding { .. } | PressState::Dragging
+            ) {
+                release(press.last_pos, Modifiers::NONE, events);
+            }
+            events.push(Event::PointerGone);
+        }
+        self.stop_inertia(events);
+    }
+
+    /// Fire long presses, settle drags and scroll with inertia, called before every pass
+    pub fn update(
+        &mut self,
+        ctx: &egui::Context,
+        mode: &TouchMode,
+        now: Instant,
+        modifiers: Modifiers,
+        events: &mut Vec<Event>,
+    ) {
+        if let Some(press) = &mut self.press {
+            match press.state {
+                // A finger that rested long enough is a secondary click
+                PressState::Resting => {
+                    if now.duration_since(press.start) >= mode.long_press_duration {
+                        let pos = press.start_pos;
+                        self.press = None;
+                        click(pos, PointerButton::Secondary, modifiers, events);
+                    }
+                }
+                // Let the coming pass see the press
+                PressState::Deciding { seen: false } => {
+                    press.state = PressState::Deciding { seen: true };
+                    ctx.request_repaint();
+                }
+                // Scroll instead when the press did not start dragging anything
+                PressState::Deciding { seen: true } => {
+                    if ctx.dragged_id().is_some() {
+                        press.state = PressState::Dragging;
+                    } else {
+                        // The finger moved too far for the release to be a click
+                        press.state = PressState::Scrolling;
+                        release(press.last_pos, modifiers, events);
+                    }
+                }
+                PressState::Dragging | PressState::Scrolling => {}
+            }
+        }
+
+        let Some(last_update) = self.last_update.replace(now) else {
+            return;
+        };
+        if self.inertia_pos.is_none() {
+            return;
+        }
+        let dt = now.duration_since(last_update).as_secs_f32();
+        self.velocity *= mode.inertia_friction.powf(dt);
+        if self.velocity.length() < MIN_INERTIA_VELOCITY {
+            self.stop_inertia(events);
+            return;
+        }
+        events.push(Event::MouseWheel {
+            unit: MouseWheelUnit::Point,
+            delta: self.velocity * dt,
+            modifiers,
+        });
+        // Keep scrolling without waiting for input
+        ctx.request_repaint();
+    }
+
+    /// Stop scrolling with inertia
+    fn stop_inertia(&mut self, events: &mut Vec<Event>) {
+        self.velocity = Vec2::ZERO;
+        self.last_update = None;
+        if self.inertia_pos.take().is_some() {
+            events.push(Event::PointerGone);
+        }
+    }
+}
+
+/// Release the primary pointer button
+fn release(pos: Pos2, modifiers: Modifiers, events: &mut Vec<Event>) {
+    events.push(Event::PointerButton {
+        pos,
+        button: PointerButton::Primary,
+        pressed: false,
+        modifiers,
+    });
+}
+
+/// Press and release a pointer button
+fn click(pos: Pos2, button: PointerButton, modifiers: Modifiers, events: &mut Vec<Event>) {
+    for pressed in [true, false] {
+        events.push(Event::PointerButton {
+            pos,
+            button,
+            pressed,
+            modifiers,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pressed_buttons(events: &[Event]) -> Vec<PointerButton> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                Event::PointerButton {
+                    button,
+                    pressed: true,
+                    ..
+                } => Some(*button),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tap_is_a_primary_click() {
+        let (ctx, mode, now, mut events) = (
+            egui::Context::default(),
+            TouchMode::default(),
+            Instant::now(),
+            Vec::new(),
+        );
+        let mut gestures = TouchGestures::default();
+        gestures.start(&ctx, &mode, Pos2::new(10.0, 10.0), 0, now, &mut events);
+        gestures.move_to(
+            &mode,
+            Pos2::new(12.0, 10.0),
+            16,
+            Modifiers::NONE,
+            &mut events,
+        );
+        gestures.end(&ctx, &mode, now, Modifiers::NONE, &mut events);
+        assert_eq!(pressed_buttons(&events), [PointerButton::Primary]);
+        assert!(!gestures.is_active());
+    }
+
+    #[test]
+    fn long_press_is_a_secondary_click() {
+        let (ctx, mode, now, mut events) = (
+            egui::Context::default(),
+            TouchMode::default(),
+            Instant::now(),
+            Vec::new(),
+        );
+        let mut gestures = TouchGestures::default();
+        gestures.start(&ctx, &mode, Pos2::new(10.0, 10.0), 0, now, &mut events);
+        gestures.update(
+            &ctx,
+            &mode,
+            now + Duration::from_millis(100),
+            Modifiers::NONE,
+            &mut events,
+        );
+        assert!(pressed_buttons(&events).is_empty());
+
+        gestures.update(
+            &ctx,
+            &mode,
+            now + Duration::from_secs(1),
+            Modifiers::NONE,
+            &mut events,
+        );
+        gestures.end(
+            &ctx,
+            &mode,
+            now + Duration::from_secs(1),
+            Modifiers::NONE,
+            &mut events,
+        );
+        assert_eq!(pressed_buttons(&events), [PointerButton::Secondary]);
+    }
+
+    #[test]
+    fn long_press_without_a_pass_is_a_secondary_click() {
+        let (ctx, mode, now, mut events) = (
+            egui::Context::default(),
+            TouchMode::default(),
+            Instant::now(),
+            Vec::new(),
+        );
+        let mut gestures = TouchGestures::default();
+        gestures.start(&ctx, &mode, Pos2::new(10.0, 10.0), 0, now, &mut events);
+        gestures.end(
+            &ctx,
+            &mode,
+            now + Duration::from_secs(1),
+            Modifiers::NONE,
+            &mut events,
+        );
+        assert_eq!(pressed_buttons(&events), [PointerButton::Secondary]);
+    }
+
+    #[test]
+    fn drag_over_nothing_draggable_scrolls_and_keeps_going() {
+        let (ctx, mode, now, mut events) = (
+            egui::Context::default(),
+            TouchMode::default(),
+            Instant::now(),
+            Vec::new(),
+        );
+        let mut gestures = TouchGestures::default();
+        gestures.start(&ctx, &mode, Pos2::new(10.0, 100.0), 0, now, &mut events);
+        gestures.move_to(
+            &mode,
+            Pos2::new(10.0, 80.0),
+            16,
+            Modifiers::NONE,
+            &mut events,
+        );
+        // The press goes to egui first, so it can drag what is under the finger
+        assert_eq!(pressed_buttons(&events), [PointerButton::Primary]);
+
+        // Two passes later nothing is dragged, so the press is released and the finger scrolls
+        for _ in 0..2 {
+            gestures.update(&ctx, &mode, now, Modifiers::NONE, &mut events);
+        }
+        assert!(matches!(
+            events.last(),
+            Some(Event::PointerButton { pressed: false, .. })
+        ));
+        gestures.move_to(
+            &mode,
+            Pos2::new(10.0, 60.0),
+            32,
+            Modifiers::NONE,
+            &mut events,
+        );
+        gestures.move_to(
+            &mode,
+            Pos2::new(10.0, 40.0),
+            48,
+            Modifiers::NONE,
+            &mut events,
+        );
+        gestures.end(&ctx, &mode, now, Modifiers::NONE, &mut events);
+        assert!(gestures.is_active());
+
+        events.clear();
+        gestures.update(
+            &ctx,
+            &mode,
+            now + Duration::from_millis(16),
+            Modifiers::NONE,
+            &mut events,
+        );
+        assert!(matches!(
+            events[..],
+            [Event::MouseWheel { delta, .. }] if delta.y < 0.0
+        ));
+
+        gestures.update(
+            &ctx,
+            &mode,
+            now + Duration::from_secs(10),
+            Modifiers::NONE,
+            &mut events,
+        );
+        assert!(!gestures.is_active());
+    }
+}